@@ -39,13 +39,11 @@ impl Board {
     }
 
     fn test_line(&self, y: usize) -> bool {
-        (0..self.width).map(|x| self.marks[x][y])
-            .all(|b| b)
+        (0..self.width).all(|x| self.marks[x][y])
     }
 
     fn test_column(&self, x: usize) -> bool {
-        (0..self.height).map(|y| self.marks[x][y])
-            .all(|b| b)
+        (0..self.height).all(|y| self.marks[x][y])
     }
 
     fn score(&mut self) -> Option<isize> {
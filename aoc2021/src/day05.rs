@@ -1,6 +1,6 @@
-use std::{collections::HashMap, str::FromStr, fmt, cmp::max};
+use std::collections::HashMap;
 
-use crate::{utils::{AdventError, split_lines}, data_str};
+use crate::{utils::{AdventError, split_lines, Point2 as Point}, data_str};
 
 pub fn run() -> (usize, usize) {
     let input = data_str!("day05a");
@@ -17,22 +17,18 @@ fn count(data: &[(Point, Point)], threshold: usize, diagonals: bool) -> usize {
     let mut map: HashMap<Point, usize> = HashMap::new();
 
     for (start, end) in data {
-        let (dx, dy) = ((end.x - start.x).abs(), (end.y - start.y).abs());
+        let delta = end - start;
 
         // skip diagonals
-        if !diagonals && dx == dy {
+        if !diagonals && delta.x().abs() == delta.y().abs() {
             continue;
         }
 
-        let mut x = start.x;
-        let mut y = start.y;
-        for _ in 0..=max(dx, dy) {
-            let p = Point::new(x, y);
-
+        let direction = delta.signum();
+        let mut p = *start;
+        for _ in 0..=start.chebyshev(end) {
             *map.entry(p).or_insert(0) += 1;
-
-            x += (end.x - start.x).signum();
-            y += (end.y - start.y).signum();
+            p += direction;
         }
     }
 
@@ -51,39 +47,6 @@ fn _print_map(map: &HashMap<Point, usize>) {
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Debug, Clone)]
-struct Point {
-    x: isize,
-    y: isize
-}
-
-impl Point {
-    fn new(x: isize, y: isize) -> Self {
-        Point {x, y}
-    }
-}
-
-impl fmt::Display for Point {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{},{}", self.x, self.y)
-    }
-}
-
-impl FromStr for Point {
-    type Err = AdventError;
-
-    fn from_str(input: &str) -> Result<Self, AdventError> {
-        let mut p = input.split(',');
-
-        let x = p.next().ok_or(AdventError::NotEnoughElements)?
-            .parse()?;
-        let y = p.next().ok_or(AdventError::NotEnoughElements)?
-            .parse()?;
-
-        Ok(Point {x, y})
-    }
-}
-
 fn parse(lines: &[String]) -> Result<Vec<(Point, Point)>, AdventError> {
     fn parse_line(line: &str) -> Result<(Point, Point), AdventError> {
         let mut points = line.split(" -> ");
@@ -1,6 +1,4 @@
-use std::collections::VecDeque;
-
-use crate::{utils::{parse_single_line, split_lines}, data_str};
+use crate::{utils::{parse_single_line, split_lines, AdventError, checked::{Checked, CheckedIterator}, matrix::Matrix}, data_str};
 
 pub fn run() -> (usize, usize) {
     let input = data_str!("day06a");
@@ -8,51 +6,70 @@ pub fn run() -> (usize, usize) {
     let data = parse_single_line(&lines[0]).expect("invalid input");
 
     (
-        num_fish(&data, 80),
-        num_fish(&data, 256),
+        num_fish(&data, 80).expect("too many fish"),
+        num_fish(&data, 256).expect("too many fish"),
     )
 }
 
-fn num_fish(initial_conditions: &[u8], iterations: usize) -> usize {
-    let mut ls = LanternfishSchool::new(initial_conditions);
-    for _ in 0..iterations {
-        ls.step();
-    }
+/// The count type decides how many fish fit, e.g., `BigUint` with the feature `bigint`
+/// or `ModInt` for the count modulo some number after astronomically many days.
+fn num_fish<C: Checked>(initial_conditions: &[u8], iterations: u64) -> Result<C, AdventError> {
+    let mut ls = LanternfishSchool::new(initial_conditions)?;
+    ls.advance(iterations)?;
     ls.count()
 }
 
-struct LanternfishSchool {
+struct LanternfishSchool<C> {
     // at each index is the number of fish with the `internal timer` equal to the index
-    internal_timers: VecDeque<usize>
+    internal_timers: Vec<C>
 }
 
-impl LanternfishSchool {
-    fn new(individuals: &[u8]) -> Self {
-        let mut internal_timers = VecDeque::from(vec![0; 9]);
+impl<C: Checked> LanternfishSchool<C> {
+    fn new(individuals: &[u8]) -> Result<Self, AdventError> {
+        let mut internal_timers = vec![C::zero(); 9];
 
         for &i in individuals {
-            internal_timers[i as usize] += 1;
+            internal_timers[i as usize] = internal_timers[i as usize].try_add(&C::one())?;
         }
 
-        LanternfishSchool {
+        Ok(LanternfishSchool {
             internal_timers
-        }
+        })
     }
 
-    fn count(&self) -> usize {
+    fn count(&self) -> Result<C, AdventError> {
         self.internal_timers
             .iter()
-            .sum()
+            .cloned()
+            .checked_sum()
+    }
+
+    /// The linear map of a single day on the timer counts.
+    fn transition() -> Matrix<C> {
+        let mut day = Matrix::zero(9, 9);
+        // every timer counts down, ...
+        for timer in 1..9 {
+            day[(timer - 1, timer)] = C::one();
+        }
+        // ... and fish at zero reset to 6 and spawn a new one at 8
+        day[(6, 0)] = C::one();
+        day[(8, 0)] = C::one();
+        day
     }
 
-    fn step(&mut self) {
-        self.internal_timers.rotate_left(1);
-        self.internal_timers[6] += self.internal_timers[8];
+    /// Advances by `days` in `O(log days)` matrix multiplications.
+    fn advance(&mut self, days: u64) -> Result<(), AdventError> {
+        self.internal_timers = Self::transition()
+            .try_pow(days)?
+            .try_apply(&self.internal_timers)?;
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::utils::{number_theory::ModInt, matrix::{berlekamp_massey, nth_term}};
+
     use super::*;
 
     #[test]
@@ -61,8 +78,36 @@ mod tests {
 
         let data = parse_single_line(input).expect("invalid input");
 
-        assert_eq!(num_fish(&data, 18), 26);
-        assert_eq!(num_fish(&data, 80), 5934);
-        assert_eq!(num_fish(&data, 256), 26984457539);
+        assert_eq!(num_fish::<usize>(&data, 18).unwrap(), 26);
+        assert_eq!(num_fish::<usize>(&data, 80).unwrap(), 5934);
+        assert_eq!(num_fish::<usize>(&data, 256).unwrap(), 26984457539);
+        assert!(matches!(num_fish::<usize>(&data, 1000), Err(AdventError::Overflow)));
+    }
+
+    #[test]
+    fn modular() {
+        type P = ModInt<1_000_000_007>;
+
+        let data = parse_single_line("3,4,3,1,2").expect("invalid input");
+        assert_eq!(num_fish::<P>(&data, 256).unwrap(), P::from(26984457539));
+        // the exact count would have tens of billions of digits, cross check
+        // with the recurrence of the counts
+        let counts: Vec<P> = (0..20).map(|days| num_fish(&data, days).unwrap()).collect();
+        let recurrence = berlekamp_massey(&counts);
+        assert_eq!(
+            num_fish::<P>(&data, 1_000_000_000_000).unwrap(),
+            nth_term(&recurrence, &counts, 1_000_000_000_000).unwrap()
+        );
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn big() {
+        use crate::utils::BigUint;
+
+        let data = parse_single_line("3,4,3,1,2").expect("invalid input");
+        let fish: BigUint = num_fish(&data, 1000).unwrap();
+        assert_eq!(fish.to_string().len(), 39);
+        assert_eq!(num_fish::<BigUint>(&data, 256).unwrap(), BigUint::from(26984457539u64));
     }
 }
\ No newline at end of file
@@ -1,30 +1,32 @@
-use std::iter;
-
 use itertools::Itertools;
 
-use crate::{utils::Map, data_str};
+use crate::{utils::{Map, automaton::Automaton}, data_str};
 
 pub fn run() -> (usize, usize) {
     let input = data_str!("day11a");
-    let mut map: Map<u8> = input.parse().expect("invalid input");
-
-    let num = 100;
+    let map: Map<u8> = input.parse().expect("invalid input");
+    let mut octopuses = Automaton::new(map, |m: &mut Map<u8>| m.step() > 0);
 
     (
-        map.count_flashes(num),
-        map.synchronized() + num,
+        count_flashes(&mut octopuses, 100),
+        synchronized(&mut octopuses),
     )
 }
 
-impl Map<u8> {
-    fn count_flashes(&mut self, num: usize) -> usize {
-        iter::repeat_with(|| self.step()).take(num).sum()
-    }
+fn count_flashes<F: FnMut(&mut Map<u8>) -> bool>(octopuses: &mut Automaton<Map<u8>, F>, num: usize) -> usize {
+    (0..num).map(|_| {
+        octopuses.step();
+        octopuses.state.find_all(0).len()
+    }).sum()
+}
 
-    fn synchronized(&mut self) -> usize {
-        iter::repeat_with(|| self.step()).take_while(|&x| x < 100).count() + 1
-    }
+/// Total number of steps until all octopuses flash at once.
+fn synchronized<F: FnMut(&mut Map<u8>) -> bool>(octopuses: &mut Automaton<Map<u8>, F>) -> usize {
+    octopuses.run_until(|m| m.values.iter().flatten().all(|&energy| energy == 0))
+        .steps()
+}
 
+impl Map<u8> {
     fn step(&mut self) -> usize {
         let mut ctr = 0;
 
@@ -74,10 +76,10 @@ mod tests {
             5283751526
         ";
 
-        let mut map: Map<u8> = input.parse().expect("invalid input");
-        let num = 100;
+        let map: Map<u8> = input.parse().expect("invalid input");
+        let mut octopuses = Automaton::new(map, |m: &mut Map<u8>| m.step() > 0);
 
-        assert_eq!(map.count_flashes(num), 1656);
-        assert_eq!(map.synchronized() + num, 195);
+        assert_eq!(count_flashes(&mut octopuses, 100), 1656);
+        assert_eq!(synchronized(&mut octopuses), 195);
     }
 }
\ No newline at end of file
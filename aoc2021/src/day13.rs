@@ -19,17 +19,13 @@ enum Fold {
     Y(usize)
 }
 
-#[derive(PartialEq, Eq, Hash, Clone)]
-struct Point {
-    x: usize,
-    y: usize
-}
+type Point = crate::utils::Point<usize, 2>;
 
 impl FromStr for Fold {
     type Err = AdventError;
 
     fn from_str(line: &str) -> Result<Self, AdventError> {
-        let assignment = line.split(' ').last().ok_or(AdventError::NotEnoughElements)?;
+        let assignment = line.split(' ').next_back().ok_or(AdventError::NotEnoughElements)?;
         let mut it = assignment.split('=');
         let axis = it.next().ok_or(AdventError::NotEnoughElements)?;
         let position: usize = it.next().ok_or(AdventError::NotEnoughElements)?.parse()?;
@@ -41,20 +37,6 @@ impl FromStr for Fold {
     }
 }
 
-impl FromStr for Point {
-    type Err = AdventError;
-
-    fn from_str(line: &str) -> Result<Self, AdventError> {
-        let mut it = line.trim().split(',');
-        let x: usize = it.next().ok_or(AdventError::NotEnoughElements)?.parse()?;
-        let y: usize = it.next().ok_or(AdventError::NotEnoughElements)?.parse()?;
-        Ok(Point {
-            x,
-            y
-        })
-    }
-}
-
 fn parse(input: &str) -> Result<(Vec<Point>, Vec<Fold>), AdventError> {
     let mut blocks = input.trim().split("\n\n");
 
@@ -100,7 +82,7 @@ fn print_code(points: &[Point], folds: &[Fold]) -> String {
 }
 
 fn fold(points: &HashSet<Point>, fold: &Fold) -> HashSet<Point> {
-    points.iter().map(|&Point{x, y}| {
+    points.iter().map(|&Point{coords: [x, y]}| {
         let (x, y) = match fold {
             Fold::Y(y_fold) => {
                 assert!(y != *y_fold);
@@ -119,29 +101,29 @@ fn fold(points: &HashSet<Point>, fold: &Fold) -> HashSet<Point> {
                 }
             }
         };
-        Point {x, y}
+        Point::new(x, y)
     }).collect()
 }
 
 fn draw_points_on_paper_transposed(points: &HashSet<Point>) -> String {
-    let x_max = points.iter().map(|p| p.x).max().unwrap();
-    let y_max = points.iter().map(|p| p.y).max().unwrap();
+    let x_max = points.iter().map(|p| p.x()).max().unwrap();
+    let y_max = points.iter().map(|p| p.y()).max().unwrap();
 
     let mut paper = vec![vec![false; y_max+1]; x_max+1];
     for p in points {
-        paper[p.x][p.y] = true;
+        paper[p.x()][p.y()] = true;
     }
 
     paper_to_string(&paper)
 }
 
 fn draw_points_on_paper(points: &HashSet<Point>) -> String {
-    let x_max = points.iter().map(|p| p.x).max().unwrap();
-    let y_max = points.iter().map(|p| p.y).max().unwrap();
+    let x_max = points.iter().map(|p| p.x()).max().unwrap();
+    let y_max = points.iter().map(|p| p.y()).max().unwrap();
 
     let mut paper = vec![vec![false; x_max+1]; y_max+1];
     for p in points {
-        paper[p.y][p.x] = true;
+        paper[p.y()][p.x()] = true;
     }
 
     paper_to_string(&paper)
@@ -1,64 +1,59 @@
 use std::{str::FromStr, collections::HashMap};
 
 use itermore::IterMore;
-use itertools::Itertools;
-
-use crate::{utils::AdventError, data_str};
+use crate::{utils::{AdventError, checked::Checked, matrix::Matrix}, data_str};
 
 pub fn run() -> (isize, isize) {
     let input = data_str!("day14a");
     let (template, rules) = parse(input).expect("invalid input");
 
     (
-        calculate(&template, &rules, 10),
-        calculate(&template, &rules, 40),
+        calculate(&template, &rules, 10).expect("too many elements"),
+        calculate(&template, &rules, 40).expect("too many elements"),
     )
 }
 
-fn calculate(template: &str, rules: &[Rule], iterations: usize) -> isize {
-    let mut result: HashMap<(char, char), isize> = HashMap::new();
+/// Difference between the most and least common element after `iterations`
+/// insertion steps, in `O(log iterations)` matrix multiplications on the pair counts.
+fn calculate(template: &str, rules: &[Rule], iterations: u64) -> Result<isize, AdventError> {
+    let index: HashMap<(char, char), usize> = rules.iter()
+        .enumerate()
+        .map(|(i, r)| (r.pattern, i))
+        .collect();
+    let lookup = |pair: (char, char)| index.get(&pair).copied().ok_or_else(||
+        AdventError::UnexpectedElement { found: format!("{}{}", pair.0, pair.1), expected: &["a pair with a rule"] }
+    );
 
+    let mut pairs = vec![0; rules.len()];
     for pair in template.chars()
         .windows()
         .map(|w: [char; 2]| (w[0], w[1]))
     {
-        *result.entry(pair).or_insert(0) += 1;
+        pairs[lookup(pair)?] += 1;
     }
 
-    let rule_map: HashMap<(char, char), char> = rules.iter()
-        .map(|r| (r.pattern, r.insertion))
-        .collect();
-
-    for _ in 0..iterations {
-        let to_insert = result.iter()
-            .flat_map(|(&pair, &count)| {
-                let c = rule_map[&pair];
-                [
-                    ((pair.0, c), count),
-                    ((c, pair.1), count),
-                    (pair, -count),
-                ]
-            })
-            .collect_vec();
-
-        for (pair, count) in to_insert {
-            *result.entry(pair).or_insert(0) += count;
+    // every pair is replaced by the two pairs around its insertion
+    let mut step = Matrix::zero(rules.len(), rules.len());
+    for (i, r) in rules.iter().enumerate() {
+        for new in [(r.pattern.0, r.insertion), (r.insertion, r.pattern.1)] {
+            step[(lookup(new)?, i)] += 1;
         }
     }
+    let pairs = step.try_pow(iterations)?.try_apply(&pairs)?;
 
     let mut counter: HashMap<char, isize> = HashMap::new();
-    for ((c1, _c2), count) in result {
-        *counter.entry(c1).or_insert(0) += count;
+    for (r, count) in rules.iter().zip(pairs) {
+        let entry = counter.entry(r.pattern.0).or_insert(0);
         // only count the first one, to avoid counting twice
-        // *counter.entry(_c2).or_insert(0) += count;
+        *entry = entry.try_add(&count)?;
     }
     // then insert the last element of the template (which is never the first element)
-    *counter.entry(template.chars().last().unwrap()).or_insert(0) += 1;
+    *counter.entry(template.chars().last().ok_or(AdventError::NotEnoughElements)?).or_insert(0) += 1;
 
-    let min = *counter.values().min().unwrap();
-    let max = *counter.values().max().unwrap();
+    let min = *counter.values().min().ok_or(AdventError::NotEnoughElements)?;
+    let max = *counter.values().max().ok_or(AdventError::NotEnoughElements)?;
 
-    max - min
+    Ok(max - min)
 }
 
 struct Rule {
@@ -136,7 +131,8 @@ mod tests {
 
         let (template, rules) = parse(input).expect("invalid input");
 
-        assert_eq!(calculate(&template, &rules, 10), 1588);
-        assert_eq!(calculate(&template, &rules, 40), 2188189693529);
+        assert_eq!(calculate(&template, &rules, 10).unwrap(), 1588);
+        assert_eq!(calculate(&template, &rules, 40).unwrap(), 2188189693529);
+        assert!(matches!(calculate(&template, &rules, 100), Err(AdventError::Overflow)));
     }
 }
@@ -1,4 +1,4 @@
-use crate::{utils::{Map, shortest_path::astar}, data_str};
+use crate::{utils::{Map, Coord, shortest_path::{Neighborful, Observer, astar_observed}}, data_str};
 
 pub fn run() -> (usize, usize) {
     let input = data_str!("day15a");
@@ -12,12 +12,22 @@ pub fn run() -> (usize, usize) {
 
 impl Map<u8> {
     fn lowest_risk(&self) -> usize {
+        self.lowest_risk_observed(&mut ())
+    }
+
+    fn lowest_risk_observed(&self, observer: &mut impl Observer<Coord>) -> usize {
         let start = (0, 0);
         let end = (self.width-1, self.height-1);
 
-        let shortest = astar(&self, start, end);
+        let (risk, _path) = astar_observed(
+            start,
+            |&c| self.neighbors(c).map(|n| (n, self[n] as usize)),
+            |&c| <&Map<u8>>::distance(c, end),
+            |&c| c == end,
+            observer
+        ).expect("no path found");
 
-        shortest.iter().rev().skip(1).map(|p| self[*p] as usize).sum()
+        risk
     }
 
     fn expand(&self) -> Map<u8> {
@@ -53,6 +63,8 @@ impl Map<u8> {
 
 #[cfg(test)]
 mod tests {
+    use crate::utils::visualize::SearchRenderer;
+
     use super::*;
 
     #[test]
@@ -73,6 +85,10 @@ mod tests {
         let map: Map<u8> = input.parse().expect("invalid input");
 
         assert_eq!(map.lowest_risk(), 40);
+
+        let mut renderer = SearchRenderer::buffer(&map, |&risk| (b'0' + risk) as char, 25);
+        assert_eq!(map.lowest_risk_observed(&mut renderer), 40);
+        assert!(renderer.text().contains("path of 18 steps"));
         assert_eq!(map.expand().lowest_risk(), 315);
     }
 }
\ No newline at end of file
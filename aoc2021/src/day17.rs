@@ -1,9 +1,8 @@
-use std::{str::FromStr, cmp};
+use std::{str::FromStr, cmp, ops::Range};
 
-use itertools::Itertools;
 use scan_fmt::scan_fmt;
 
-use crate::{utils::AdventError, data_str};
+use crate::{utils::{AdventError, intervals::IntervalSet, number_theory::{quadratic_roots, triangular_root}}, data_str};
 
 pub fn run() -> (isize, usize) {
     let line = data_str!("day17a");
@@ -22,56 +21,75 @@ struct TargetZone {
     y_max: isize,
 }
 
+/// Triangular number, the distance covered until a velocity of `v` decays to zero.
+fn triangular(v: i128) -> i128 {
+    v * (v + 1) / 2
+}
+
+/// The probe's position after `t` steps is `v t - t (t - 1) / 2` (until the
+/// horizontal velocity stalls), so all questions reduce to exact integer roots
+/// of quadratics. Assumes that the zone is right of and below the launcher.
 impl TargetZone {
     fn highest_point(&self) -> isize {
-        let vx_max: isize = self.x_max;
-        let vy_min: isize = 0;
-        let vy_max: isize = -self.y_min;
-
-        (0..vx_max).cartesian_product(vy_min..vy_max)
-            .filter_map(|(vx, vy)| self.test(vx, vy))
-            .max()
+        // the probe returns to height 0 with `-vy - 1`, so faster ones overshoot
+        (self.y_min..-self.y_min)
+            .rev()
+            .find(|&vy| !self.velocities_x(vy).is_empty())
+            .map(|vy| triangular(cmp::max(vy, 0) as i128) as isize)
             .unwrap()
     }
 
     fn count_trajectories(&self) -> usize {
-        let vx_max: isize = self.x_max;
-        let vy_min: isize = self.y_min;
-        let vy_max: isize = cmp::max(self.y_max, self.y_min.abs());
-
-        (0..=vx_max).cartesian_product(vy_min..=vy_max)
-            .filter_map(|(vx, vy)| self.test(vx, vy))
-            .count()
+        (self.y_min..-self.y_min)
+            .map(|vy| self.velocities_x(vy).total_length() as usize)
+            .sum()
     }
 
-    fn test(&self, mut vx: isize, mut vy: isize) -> Option<isize> {
-        let mut x = 0;
-        let mut y = 0;
-
-        let mut high = None;
-        let mut watermark = 0;
-
-        while x <= self.x_max && y >= self.y_min {
-            x += vx;
-            if vx > 0 {
-                vx -= 1;
-            }
-
-            y += vy;
-            vy -= 1;
-
-            watermark = cmp::max(y, watermark);
+    /// The highest point of a trajectory hitting the zone, `None` if it misses.
+    #[cfg(test)]
+    fn test(&self, vx: isize, vy: isize) -> Option<isize> {
+        self.velocities_x(vy)
+            .contains(vx as i128)
+            .then(|| triangular(cmp::max(vy, 0) as i128) as isize)
+    }
 
-            if self.contains(x, y) {
-                high = Some(watermark);
-            }
-        }
+    /// The horizontal velocities, which hit the zone together with `vy`.
+    fn velocities_x(&self, vy: isize) -> IntervalSet<i128> {
+        self.steps_in_y(vy)
+            .map(|(first, last)| (first..=last).map(|t| self.velocities_x_after(t)).collect())
+            .unwrap_or_default()
+    }
 
-        high
+    /// The first and last step, at which a probe with `vy` is at the height of the zone.
+    fn steps_in_y(&self, vy: isize) -> Option<(i128, i128)> {
+        let b = -(2 * vy as i128 + 1);
+        // coefficients of isize coordinates are too small to overflow
+        let roots = |c: i128| quadratic_roots(1, b, c).expect("coefficients fit");
+        // y(t) >= y_min, if t^2 + b t + 2 y_min <= 0
+        let (_, last) = roots(2 * self.y_min as i128)?;
+        // y(t) > y_max between the roots of t^2 + b t + 2 y_max + 1, which include 0
+        let first = roots(2 * self.y_max as i128 + 1).map_or(0, |(_, high)| high + 1);
+        (first <= last).then_some((first, last))
     }
 
-    fn contains(&self, x: isize, y: isize) -> bool {
-        x >= self.x_min && x <= self.x_max && y >= self.y_min && y <= self.y_max
+    /// The horizontal velocities, for which a probe is above the zone after `t > 0` steps.
+    ///
+    /// The position is monotonic in the velocity, so this is a single range.
+    fn velocities_x_after(&self, t: i128) -> Range<i128> {
+        // all velocities up to `t - 1` have stalled at their triangular number
+        let stalled = triangular(t - 1);
+        let low = if self.x_min as i128 <= stalled {
+            triangular_root(self.x_min as u128 - 1) as i128 + 1
+        } else {
+            // ceiling of the solution of `v t - stalled = x_min`
+            -(-(self.x_min as i128 + stalled)).div_euclid(t)
+        };
+        let high = if (self.x_max as i128) < stalled {
+            triangular_root(self.x_max as u128) as i128
+        } else {
+            (self.x_max as i128 + stalled).div_euclid(t)
+        };
+        low..high + 1
     }
 }
 
@@ -98,6 +116,27 @@ impl FromStr for TargetZone {
 mod tests {
     use super::*;
 
+    /// Step by step flight, the highest point if it hits the zone.
+    fn simulate(target: &TargetZone, mut vx: isize, mut vy: isize) -> Option<isize> {
+        let (mut x, mut y) = (0, 0);
+        let mut high = None;
+        let mut watermark = 0;
+
+        while x <= target.x_max && y >= target.y_min {
+            x += vx;
+            vx -= vx.signum();
+            y += vy;
+            vy -= 1;
+            watermark = cmp::max(y, watermark);
+
+            if (target.x_min..=target.x_max).contains(&x) && (target.y_min..=target.y_max).contains(&y) {
+                high = Some(watermark);
+            }
+        }
+
+        high
+    }
+
     #[test]
     fn example() {
         let input = r"target area: x=20..30, y=-10..-5";
@@ -108,4 +147,26 @@ mod tests {
         assert_eq!(target.highest_point(), 45);
         assert_eq!(target.count_trajectories(), 112);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn analytic() {
+        for input in [
+            "target area: x=20..30, y=-10..-5",
+            "target area: x=1..1, y=-1..-1",
+            "target area: x=7..9, y=-30..-28",
+            "target area: x=192..251, y=-89..-59",
+        ] {
+            let target: TargetZone = input.parse().expect("invalid input");
+            for vx in 0..=target.x_max {
+                for vy in target.y_min..=-target.y_min {
+                    assert_eq!(target.test(vx, vy), simulate(&target, vx, vy), "{input} {vx},{vy}");
+                }
+            }
+        }
+
+        // far beyond a brute force search
+        let huge: TargetZone = "target area: x=1000000000..1000010000, y=-2000000000..-1999999000"
+            .parse().expect("invalid input");
+        assert_eq!(huge.highest_point(), 1999999999000000000);
+    }
+}
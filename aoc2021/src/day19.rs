@@ -1,10 +1,8 @@
-use std::{collections::HashSet, str::FromStr, ops::{Sub, Add}};
+use std::collections::HashSet;
 
-use itertools::{Itertools, iproduct};
-use scan_fmt::scan_fmt;
-use rayon::prelude::*;
+use itertools::Itertools;
 
-use crate::{utils::AdventError, data_str};
+use crate::{utils::{AdventError, Point3 as Point, rotation::register}, data_str};
 
 pub fn run() -> (usize, usize) {
     let input = data_str!("day19a");
@@ -17,99 +15,6 @@ pub fn run() -> (usize, usize) {
     )
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
-struct Point {
-    x: isize,
-    y: isize,
-    z: isize
-}
-
-impl Point {
-    fn all_orientations(&self) -> impl Iterator<Item=Point> + '_ {
-        iproduct!(&[-1, 1], &[-1, 1], &[-1, 1]).flat_map(|(i, j, k)| {
-            [i*self.x, j*self.y, k*self.z].iter()
-                .cloned()
-                .permutations(3)
-                .map(|v| {
-                    Point{
-                        x: v[0],
-                        y: v[1],
-                        z: v[2],
-                    }
-                }).collect_vec()
-        })
-    }
-
-    fn is_zero(&self) -> bool {
-        self.x == 0 && self.y == 0 && self.z == 0
-    }
-
-    fn manhattan(&self, other: &Point) -> usize {
-        (
-            (self.x - other.x).abs()
-            + (self.y - other.y).abs()
-            + (self.z - other.z).abs()
-        )
-        as usize
-    }
-}
-
-impl Sub for &Point {
-    type Output = Point;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Point {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-            z: self.z - rhs.z,
-        }
-    }
-}
-
-impl Sub for Point {
-    type Output = Point;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Point {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-            z: self.z - rhs.z,
-        }
-    }
-}
-
-impl Add for &Point {
-    type Output = Point;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Point {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
-        }
-    }
-}
-
-impl FromStr for Point {
-    type Err = AdventError;
-
-    fn from_str(line: &str) -> Result<Self, AdventError> {
-        let (x, y, z) = scan_fmt!(
-            line,
-            "{},{},{}",
-            isize, isize, isize
-        )?;
-
-        Ok(
-            Point {
-                x,
-                y,
-                z
-            }
-        )
-    }
-}
-
 #[derive(Clone)]
 struct Scanner {
     position: Point,
@@ -119,55 +24,19 @@ struct Scanner {
 impl Scanner {
     fn new() -> Scanner {
         Scanner {
-            position: Point {x: 0, y: 0, z: 0},
+            position: Point::zero(),
             beacons: Vec::new()
         }
     }
 
-    fn all_orientations(&self) -> Vec<Vec<Point>> {
-        let mut out: Vec<Vec<Point>> = vec![Vec::new(); 48];
-
-        for p in &self.beacons {
-            for (n, o) in p.all_orientations().enumerate() {
-                out[n].push(o)
-            }
-        }
-
-        out
-    }
-
     fn overlap(&self, other: &Scanner) -> Option<Scanner> {
-        other.all_orientations().par_iter().map(|o| {
-            for j in o {
-                for i in &self.beacons {
-                    let delta = i - j;
-                    // shift by the delta of all pairs
-                    // and look if there are >= 12 overlapping
-                    let matches = self.beacons.iter().flat_map(|b| {
-                        let shifted = b - &delta;
-                        o.iter().cloned().map(move |a| {
-                            shifted.clone() - a
-                        })
-                    }).filter(|d| d.is_zero())
-                    .count();
-
-                    if matches >= 12 {
-                        // then save the shift and maybe return a
-                        // shifted and rotated replacement for other
-                        let out = Scanner {
-                            position: &other.position - &delta,
-                            beacons: o.iter().map(|p| {
-                                p + &delta
-                            }).collect()
-                        };
-                        return Some(out)
-                    }
-                }
+        // other is located if at least 12 of its beacons coincide with ours
+        register(&self.beacons, &other.beacons, 12).map(|transform|
+            Scanner {
+                position: transform.apply(&other.position),
+                beacons: transform.apply_all(&other.beacons),
             }
-            None
-        })
-        .flatten()
-        .find_any(|_| true)
+        )
     }
 }
 
@@ -214,7 +83,7 @@ fn count_beacons(scanners: &[Scanner]) -> usize {
     let mut beacons: HashSet<Point> = HashSet::new();
     for scanner in scanners {
         for p in &scanner.beacons {
-            beacons.insert(p.clone());
+            beacons.insert(*p);
         }
     }
 
@@ -225,7 +94,7 @@ fn scanner_distance(scanners: &[Scanner]) -> usize {
     scanners.iter()
         .combinations(2)
         .map(|pair|
-            pair[0].position.manhattan(&pair[1].position)
+            pair[0].position.manhattan(&pair[1].position) as usize
         )
         .max()
         .unwrap()
@@ -379,7 +248,7 @@ mod tests {
         let scanners = parse(input).expect("invalid input");
         let scanners = locate_scanners(&scanners);
 
-        println!("{:?}", scanners.iter().map(|s| s.position.clone()).collect_vec());
+        println!("{:?}", scanners.iter().map(|s| s.position).collect_vec());
 
         assert_eq!(count_beacons(&scanners), 79);
         assert_eq!(scanner_distance(&scanners), 3621);
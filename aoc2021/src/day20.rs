@@ -1,6 +1,4 @@
-use rayon::prelude::*;
-
-use crate::{utils::{Map, AdventError, binary}, data_str};
+use crate::{utils::{AdventError, automaton::{Automaton, Boundary, Neighborhood}, bitgrid::BitGrid}, data_str};
 
 pub fn run() -> (usize, usize) {
     let input = data_str!("day20a");
@@ -12,88 +10,56 @@ pub fn run() -> (usize, usize) {
     )
 }
 
-impl Map<u8> {
-    fn get(&self, x: isize, y: isize) -> Option<u8> {
-        if x < 0 || x >= self.width as isize || y < 0 || y >= self.height as isize {
-            None
-        } else {
-            Some(self.values[y as usize][x as usize])
-        }
-    }
-
-    fn block(&self, center: (isize, isize), default: u8) -> usize {
-        let mut out = Vec::new();
-        let (x, y) = center;
-
-        for j in y-1..=y+1 {
-            for i in x-1..=x+1 {
-                out.push(self.get(i, j).unwrap_or(default));
-            }
-        }
-
-        binary::to_usize(&out)
-    }
+/// An infinite image, which has the value `background` outside of `pixels`.
+#[derive(Clone)]
+struct Image {
+    pixels: BitGrid,
+    background: bool,
+}
 
-    fn enhance(&self, rules: &[u8], default: u8) -> Map<u8> {
-        let width = self.width+2;
-        let height = self.height+2;
-
-        let values = (0..height).into_par_iter().map(|j| {
-            (0..width).map(|i| {
-                let idx = self.block((i as isize - 1, j as isize - 1), default);
-                rules[idx]
-            }).collect()
-        }).collect();
-
-        Map {
-            width,
-            height,
-            values
-        }
+impl Image {
+    fn enhance(&mut self, rules: &[bool]) {
+        // every enhancement can affect one more pixel in every direction
+        let pixels = self.pixels.padded(1, self.background);
+        let boundary = Boundary::Constant(self.background);
+        let block: Vec<BitGrid> = Neighborhood::Block.offsets().iter()
+            .map(|&(dx, dy)| pixels.offset(dx, dy, &boundary))
+            .collect();
+
+        self.pixels = BitGrid::lookup(&block, rules);
+        self.background = rules[if self.background {rules.len() - 1} else {0}];
     }
 
-    fn multi_enhance(&self, rules: &[u8], n: usize) -> Map<u8> {
-        let default = 0;
-        let mut image = self.enhance(rules, default);
-        for _ in 0..n-1 {
-            let default = image[(0,0)];
-            image = image.enhance(rules, default);
-        }
+    fn multi_enhance(&self, rules: &[bool], n: usize) -> Image {
+        let mut automaton = Automaton::new(self.clone(), |image: &mut Image| {
+            image.enhance(rules);
+            true
+        });
+        automaton.run(n);
 
-        image
+        automaton.state
     }
 
     fn count_light(&self) -> usize {
-        let mut ctr = 0;
-        for j in 0..self.height {
-            for i in 0..self.width {
-                if self[(i, j)] > 0 {
-                    ctr += 1;
-                }
-            }
-        }
-
-        ctr
+        assert!(!self.background, "infinitely many pixels are lit");
+        self.pixels.count_ones()
     }
 }
 
-fn parse(input: &str) -> Result<(Vec<u8>, Map<u8>), AdventError> {
+fn parse(input: &str) -> Result<(Vec<bool>, Image), AdventError> {
     let mut it = input.split("\n\n");
     let rules = it.next()
         .ok_or(AdventError::NotEnoughElements)?
         .trim()
         .chars()
-        .map(|p| if p == '#' {1} else {0})
+        .map(|p| p == '#')
         .collect();
 
-    let map: Map<u8> = it.next()
+    let pixels = it.next()
         .ok_or(AdventError::NotEnoughElements)?
-        .trim()
-        .replace('#', "1")
-        .replace('.', "0")
         .parse()?;
 
-    Ok((rules, map))
+    Ok((rules, Image { pixels, background: false }))
 }
 
 #[cfg(test)]
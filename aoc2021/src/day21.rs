@@ -2,14 +2,14 @@ use std::{str::FromStr, cmp};
 
 use itertools::{Itertools, iproduct};
 use scan_fmt::scan_fmt;
-use cached::proc_macro::cached;
 
-use crate::{utils::AdventError, data_str};
+use crate::{utils::{AdventError, search::Memo}, data_str};
 
 pub fn run() -> (usize, usize) {
     let input = data_str!("day21a");
     let mut start: DiracDice = input.parse().expect("invalid input");
-    let (w1, w2) = dirac_dice_wins(start.players[0] as u8, start.players[1] as u8);
+    let (w1, w2) = dirac_dice_wins(start.players[0] as u8, start.players[1] as u8)
+        .expect("too many universes");
 
     (
         start.two_players(),
@@ -37,7 +37,7 @@ impl DeterministicDice {
 impl Rollable for DeterministicDice {
     fn roll(&mut self) -> usize {
         self.ctr += 1;
-        if self.ctr % 100 == 0 {
+        if self.ctr.is_multiple_of(100) {
             100
         } else {
             self.ctr % 100
@@ -62,7 +62,7 @@ impl DiracDice {
         while self.scores.iter().max().unwrap() < &1000 {
             let d = (0..3).map(|_| self.dice.roll()).sum::<usize>();
             self.players[idx] += d;
-            self.players[idx] = if self.players[idx] % 10 == 0 {10} else {self.players[idx] % 10};
+            self.players[idx] = if self.players[idx].is_multiple_of(10) {10} else {self.players[idx] % 10};
 
             self.scores[idx] += self.players[idx];
             idx += 1;
@@ -96,17 +96,30 @@ impl FromStr for DiracDice {
     }
 }
 
-fn dirac_dice_wins(start1: u8, start2: u8) -> (usize, usize) {
-    count_wins(start1, start2, 0, 0, true)
+/// State of a game with the Dirac dice, before the active player rolls.
+#[derive(Clone, Hash, PartialEq, Eq)]
+struct Game {
+    positions: [u8; 2],
+    scores: [u8; 2],
+    active: usize,
 }
 
-#[cached]
-fn count_wins(start1: u8, start2: u8, score1: u8, score2: u8, p1active: bool) -> (usize, usize) {
-    if score1 >= 21 {
-        return (1, 0)
+fn dirac_dice_wins(start1: u8, start2: u8) -> Result<(usize, usize), AdventError> {
+    let mut wins = Memo::new(&count_wins);
+    wins.get(Game {
+        positions: [start1, start2],
+        scores: [0, 0],
+        active: 0,
+    }).ok_or(AdventError::Overflow)
+}
+
+/// The number of universes in which each player wins, `None` if it overflows.
+fn count_wins(memo: &mut Memo<Game, Option<(usize, usize)>>, game: &Game) -> Option<(usize, usize)> {
+    if game.scores[0] >= 21 {
+        return Some((1, 0))
     }
-    if score2 >= 21 {
-        return (0, 1)
+    if game.scores[1] >= 21 {
+        return Some((0, 1))
     }
 
     let mut wins1 = 0;
@@ -114,27 +127,19 @@ fn count_wins(start1: u8, start2: u8, score1: u8, score2: u8, p1active: bool) ->
 
     for (i, j, k) in iproduct!(1..=3, 1..=3, 1..=3) {
         let d = i + j + k;
-        let mut s1 = start1;
-        let mut s2 = start2;
-        let mut sc1 = score1;
-        let mut sc2 = score2;
-
-        if p1active {
-            s1 += d;
-            s1 = if s1 % 10 == 0 {10} else {s1 % 10};
-            sc1 += s1;
-        } else {
-            s2 += d;
-            s2 = if s2 % 10 == 0 {10} else {s2 % 10};
-            sc2 += s2;
-        }
-
-        let (w1, w2) = count_wins(s1, s2, sc1, sc2, !p1active);
-        wins1 += w1;
-        wins2 += w2;
+        let mut next = game.clone();
+        let p = &mut next.positions[game.active];
+        *p += d;
+        *p = if p.is_multiple_of(10) {10} else {*p % 10};
+        next.scores[game.active] += *p;
+        next.active = 1 - game.active;
+
+        let (w1, w2) = memo.get(next)?;
+        wins1 = w1.checked_add(wins1)?;
+        wins2 = w2.checked_add(wins2)?;
     }
 
-    (wins1, wins2)
+    Some((wins1, wins2))
 }
 
 #[cfg(test)]
@@ -150,7 +155,7 @@ mod tests {
 
         let mut start: DiracDice = input.parse().expect("invalid input");
 
-        let (w1, w2) = dirac_dice_wins(start.players[0] as u8, start.players[1] as u8);
+        let (w1, w2) = dirac_dice_wins(start.players[0] as u8, start.players[1] as u8).unwrap();
         assert_eq!(start.two_players(), 739785);
 
         assert_eq!(w1, 444356092776315);
@@ -1,10 +1,10 @@
-use std::{str::FromStr, cmp, collections::HashSet};
+use std::str::FromStr;
 
 use scan_fmt::scan_fmt;
 
-use crate::{utils::AdventError, data_str};
+use crate::{utils::{AdventError, boxes::{AABox, BoxSet, CompressedGrid}}, data_str};
 
-pub fn run() -> (usize, isize) {
+pub fn run() -> (usize, usize) {
     let input = data_str!("day22a");
     let cuboids: Cuboids = input.parse().expect("invalid input");
 
@@ -17,12 +17,7 @@ pub fn run() -> (usize, isize) {
 #[derive(Hash, PartialEq, Eq, Debug)]
 struct Cuboid {
     on: bool,
-    x_min: isize,
-    x_max: isize,
-    y_min: isize,
-    y_max: isize,
-    z_min: isize,
-    z_max: isize,
+    region: AABox<3>,
 }
 
 impl FromStr for Cuboid {
@@ -44,59 +39,17 @@ impl FromStr for Cuboid {
         )?;
 
         let on = on == "on";
+        let region = AABox::from_inclusive([x_min, y_min, z_min], [x_max, y_max, z_max]);
 
         Ok(
             Cuboid {
                 on,
-                x_min,
-                x_max,
-                y_min,
-                y_max,
-                z_min,
-                z_max,
+                region,
             }
         )
     }
 }
 
-impl Cuboid {
-    fn overlap(&self, other: &Cuboid, on: bool) -> Option<Cuboid> {
-        let x_overlap = Cuboid::overlap_direction(self.x_min, self.x_max, other.x_min, other.x_max);
-        let y_overlap = Cuboid::overlap_direction(self.y_min, self.y_max, other.y_min, other.y_max);
-        let z_overlap = Cuboid::overlap_direction(self.z_min, self.z_max, other.z_min, other.z_max);
-
-        if let (Some(x), Some(y), Some(z)) = (x_overlap, y_overlap, z_overlap) {
-            let c = Cuboid {
-                on,
-                x_min: x.0,
-                x_max: x.1,
-                y_min: y.0,
-                y_max: y.1,
-                z_min: z.0,
-                z_max: z.1,
-            };
-            return Some(c)
-        }
-
-        None
-    }
-
-    fn overlap_direction(a_min: isize, a_max: isize, b_min: isize, b_max: isize) -> Option<(isize, isize)> {
-        if a_min <= b_max && a_max >= b_min {
-            Some((
-                cmp::max(a_min, b_min),
-                cmp::min(a_max, b_max)
-            ))
-        } else {
-            None
-        }
-    }
-
-    fn size(&self) -> isize {
-        (self.x_max - self.x_min + 1) * (self.y_max - self.y_min + 1) * (self.z_max - self.z_min + 1)
-    }
-}
-
 #[derive(Debug)]
 struct Cuboids {
     members: Vec<Cuboid>
@@ -121,55 +74,34 @@ impl FromStr for Cuboids {
 
 impl Cuboids {
     fn count_on_50(&self) -> usize {
-        let mut singles = HashSet::new();
-
-        for c in &self.members {
-            let x_min = cmp::max(c.x_min, -50);
-            let x_max = cmp::min(c.x_max, 50);
-            for x in x_min..=x_max {
-                let y_min = cmp::max(c.y_min, -50);
-                let y_max = cmp::min(c.y_max, 50);
-                for y in y_min..=y_max {
-                    let z_min = cmp::max(c.z_min, -50);
-                    let z_max = cmp::min(c.z_max, 50);
-                    for z in z_min..=z_max {
-                        if c.on {
-                            singles.insert((x, y, z));
-                        } else {
-                            singles.remove(&(x, y, z));
-                        }
-                    }
-                }
+        // within the small initialization region, the compressed grid is cheap
+        let initialization = AABox::from_inclusive([-50; 3], [50; 3]);
+        let clipped: Vec<(AABox<3>, bool)> = self.members.iter()
+            .filter_map(|c| c.region.intersection(&initialization).map(|r| (r, c.on)))
+            .collect();
+
+        let mut grid = CompressedGrid::new(clipped.iter().map(|(r, _)| r));
+        for (r, on) in &clipped {
+            if *on {
+                grid.insert(r);
+            } else {
+                grid.remove(r);
             }
         }
 
-        singles.len()
+        grid.volume()
     }
 
-    fn count_on(&self) -> isize {
-        if self.members.is_empty() {
-            return 0
-        }
-
-        let mut sum = 0;
-        for (n, c) in self.members.iter().enumerate() {
-
-            let below_me: Vec<_> = self.members[..n].iter().filter_map(|below| {
-                c.overlap(below, below.on)
-            }).collect();
-            let below_me = Cuboids {
-                members: below_me
-            };
-
-            let already_on = below_me.count_on();
-
+    fn count_on(&self) -> usize {
+        let mut set = BoxSet::new();
+        for c in &self.members {
             if c.on {
-                sum += c.size() - already_on;
+                set.insert(c.region);
             } else {
-                sum -= already_on;
+                set.remove(&c.region);
             }
         }
-        sum
+        set.volume()
     }
 }
 
@@ -1,8 +1,8 @@
-use std::{str::FromStr, fmt, cmp, collections::HashSet};
+use std::{str::FromStr, fmt};
 
 use itertools::Itertools;
 
-use crate::{utils::AdventError, data_str};
+use crate::{utils::{AdventError, shortest_path::astar}, data_str};
 
 pub fn run() -> (usize, usize) {
     let input = data_str!("day23a");
@@ -224,7 +224,7 @@ fn sort_room(s: &mut Situation, species: Amphipod) {
     let room = s.room_mut(species);
     let mut energy_delta = 0;
 
-    if room.iter().any(|&a| a == Amphipod::None) {
+    if room.contains(&Amphipod::None) {
         // now we sort the room:
         // going from bottom to top, we move down if we encounter `species`
         // until we encounter another species,
@@ -327,36 +327,26 @@ fn possible_moves(s: &Situation) -> Vec<Situation> {
     out
 }
 
-// brute force branch and bound
-fn move_all(situation: Situation) -> Option<usize> {
-    let mut best = if situation.four {60000} else {20000};
-    let mut to_check = vec![situation];
-    let mut encountered = HashSet::new();
-
-    while let Some(s) = to_check.pop() {
-        if s.finished() {
-            best = cmp::min(best, s.spent_energy);
-            continue;
-        }
-
-        if encountered.contains(&s) {
-            continue;
-        }
-
-        if s.spent_energy + lower_energy_bound(&s) > best {
-            continue;
-        }
-
-        to_check.extend(possible_moves(&s));
-
-        encountered.insert(s);
-    }
+/// The possible moves from a situation together with the energy they need.
+///
+/// The situations themselves do not carry the spent energy, such that
+/// equal situations are identified.
+fn moves(s: &Situation) -> impl Iterator<Item=(Situation, usize)> {
+    possible_moves(s).into_iter().map(|mut n| {
+        let energy = std::mem::take(&mut n.spent_energy);
+        (n, energy)
+    })
+}
 
-    Some(best)
+fn move_all(situation: Situation) -> Option<usize> {
+    astar(situation, moves, lower_energy_bound, Situation::finished)
+        .map(|(energy, _path)| energy)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::utils::search::branch_and_bound;
+
     use super::*;
 
     #[test]
@@ -436,7 +426,12 @@ mod tests {
         ";
 
         let situation: Situation = input.parse().expect("invalid input");
-        assert_eq!(move_all(situation).unwrap(), 12521);
+        assert_eq!(move_all(situation.clone()).unwrap(), 12521);
+
+        // a depth first search with pruning by the lower bound finds the same optimum
+        let (energy, stats) = branch_and_bound(situation, moves, lower_energy_bound, Situation::finished);
+        assert_eq!(energy, Some(12521));
+        assert!(stats.pruned > 0);
 
         let input = r"
             #############
@@ -449,6 +444,7 @@ mod tests {
         ";
 
         let situation: Situation = input.parse().expect("invalid input");
-        assert_eq!(move_all(situation).unwrap(), 44169);
+        assert_eq!(move_all(situation.clone()).unwrap(), 44169);
+        assert_eq!(branch_and_bound(situation, moves, lower_energy_bound, Situation::finished).0, Some(44169));
     }
 }
\ No newline at end of file
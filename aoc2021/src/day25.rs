@@ -1,11 +1,10 @@
 use std::{str::FromStr, fmt};
 
-
-use crate::{utils::AdventError, data_str};
+use crate::{utils::{AdventError, Map, automaton::{Automaton, Boundary}, bitgrid::BitGrid}, data_str};
 
 pub fn run() -> (usize, usize) {
     let input = data_str!("day25a");
-    let mut region: Region = input.parse().expect("invalid input");
+    let region: Region = input.parse().expect("invalid input");
 
     (
         region.count(),
@@ -13,74 +12,38 @@ pub fn run() -> (usize, usize) {
     )
 }
 
-#[derive(PartialEq, Eq)]
-enum Cucumber {
-    East,
-    South,
-    None
-}
-
+/// The herds of east and south facing sea cucumbers.
+#[derive(Clone, PartialEq)]
 struct Region {
-    width: usize,
-    height: usize,
-    cucumbers: Vec<Vec<Cucumber>>
+    east: BitGrid,
+    south: BitGrid,
 }
 
 impl Region {
-    fn move_east(&mut self) -> bool {
-        let mut will_move: Vec<(usize, usize, usize)> = Vec::new();
-        for j in 0..self.height {
-            for i in 0..self.width {
-                if let Cucumber::East = self.cucumbers[j][i] {
-                    let next = if i + 1 == self.width { 0 } else { i + 1 };
-                    if self.cucumbers[j][next] == Cucumber::None {
-                        will_move.push((i, j, next));
-                    }
-                }
-            }
-        }
-
-        for (i, j, next) in &will_move {
-            self.cucumbers[*j][*next] = Cucumber::East;
-            self.cucumbers[*j][*i] = Cucumber::None;
-        }
-
-        !will_move.is_empty()
+    /// Moves all sea cucumbers of `herd`, which can move in direction `(dx, dy)`.
+    fn move_herd(herd: &BitGrid, other: &BitGrid, dx: isize, dy: isize) -> (BitGrid, bool) {
+        let occupied = herd | other;
+        let free_ahead = !&occupied.offset(dx, dy, &Boundary::Periodic);
+        let moving = herd & &free_ahead;
+
+        let staying = herd & &!&moving;
+        let arrived = moving.offset(-dx, -dy, &Boundary::Periodic);
+        (&staying | &arrived, moving.any())
     }
 
-    fn move_south(&mut self) -> bool {
-        let mut will_move: Vec<(usize, usize, usize)> = Vec::new();
-        for j in 0..self.height {
-            for i in 0..self.width {
-                if let Cucumber::South = self.cucumbers[j][i] {
-                    let next = if j + 1 == self.height { 0 } else { j + 1 };
-                    if self.cucumbers[next][i] == Cucumber::None {
-                        will_move.push((i, j, next));
-                    }
-                }
-            }
-        }
-
-        for (i, j, next) in &will_move {
-            self.cucumbers[*next][*i] = Cucumber::South;
-            self.cucumbers[*j][*i] = Cucumber::None;
-        }
+    fn step(&mut self) -> bool {
+        let (east, moved_east) = Region::move_herd(&self.east, &self.south, 1, 0);
+        let (south, moved_south) = Region::move_herd(&self.south, &east, 0, 1);
+        self.east = east;
+        self.south = south;
 
-        !will_move.is_empty()
+        moved_east || moved_south
     }
 
-    fn count(&mut self) -> usize {
-        let mut ctr = 0;
-        loop {
-            ctr += 1;
-            let mut moved = false;
-            moved |= self.move_east();
-            moved |= self.move_south();
-
-            if !moved {
-                break ctr
-            }
-        }
+    fn count(self) -> usize {
+        Automaton::new(self, Region::step)
+            .run_until_fixpoint()
+            .steps()
     }
 }
 
@@ -88,27 +51,25 @@ impl FromStr for Region {
     type Err = AdventError;
 
     fn from_str(input: &str) -> Result<Self, AdventError> {
-        let cucumbers: Vec<Vec<Cucumber>> = input.trim().split('\n')
-            .map(|line| line.trim().chars()
-                .map(|i| match i {
-                    '>' => Ok(Cucumber::East),
-                    'v' => Ok(Cucumber::South),
-                    '.' => Ok(Cucumber::None),
-                    val => Err(AdventError::UnexpectedElement { found: val.to_string(), expected: &[">", "v", "."] })
-                })
-                .collect::<Result<_, _>>()
-            ).collect::<Result<_, _>>()?;
-
-        let width = cucumbers[0].len();
-        let height = cucumbers.len();
-        // assert that the board is not jagged
-        assert!(cucumbers.iter().all(|line| line.len() == width));
+        let map: Map<char> = input.parse()?;
+        let mut east = BitGrid::new(map.width, map.height);
+        let mut south = BitGrid::new(map.width, map.height);
+
+        for y in 0..map.height {
+            for x in 0..map.width {
+                match map[(x, y)] {
+                    '>' => east.set((x, y), true),
+                    'v' => south.set((x, y), true),
+                    '.' => (),
+                    val => return Err(AdventError::UnexpectedElement { found: val.to_string(), expected: &[">", "v", "."] })
+                }
+            }
+        }
 
         Ok(
             Region {
-                width,
-                height,
-                cucumbers,
+                east,
+                south,
             }
         )
     }
@@ -117,9 +78,16 @@ impl FromStr for Region {
 impl fmt::Display for Region
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                write!(f, "{} ", self.cucumbers[y][x])?;
+        for y in 0..self.east.height {
+            for x in 0..self.east.width {
+                let c = if self.east[(x, y)] {
+                    '>'
+                } else if self.south[(x, y)] {
+                    'v'
+                } else {
+                    '.'
+                };
+                write!(f, "{c} ")?;
             }
             writeln!(f)?;
         }
@@ -127,19 +95,10 @@ impl fmt::Display for Region
     }
 }
 
-impl fmt::Display for Cucumber
-{
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            Cucumber::East => write!(f, ">"),
-            Cucumber::South => write!(f, "v"),
-            Cucumber::None => write!(f, ".")
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
+    use crate::utils::cycles::{brent, Cycle};
+
     use super::*;
 
     #[test]
@@ -156,7 +115,15 @@ mod tests {
             ....v..v.>
         ";
 
-        let mut region: Region = input.parse().expect("invalid input");
+        let region: Region = input.parse().expect("invalid input");
+
+        // the herds stop moving when their orbit reaches a fixpoint
+        let cycle = brent(region.clone(), |r| {
+            let mut next = r.clone();
+            next.step();
+            next
+        });
+        assert_eq!(cycle, Cycle { tail: 57, period: 1 });
 
         assert_eq!(region.count(), 58);
     }
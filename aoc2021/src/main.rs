@@ -77,7 +77,7 @@ mod tests {
     test!{day16: (981, 299227024091)}
     test!{day17: (3916, 2986)}
     test!{day18: (3675, 4650)}
-    test!{#day19: (318, 12166)}
+    test!{day19: (318, 12166)}
     test!{day20: (5583, 19592)}
     test!{day21: (503478, 716241959649754)}
     test!{day22: (658691, 1228699515783640)}
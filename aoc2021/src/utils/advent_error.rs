@@ -18,6 +18,9 @@ pub enum AdventError {
         missing: String,
     },
 
+    #[error("Arithmetic overflow, the result does not fit into its type")]
+    Overflow,
+
     #[error(transparent)]
     IO(#[from] std::io::Error),
 
@@ -0,0 +1,349 @@
+use std::hash::Hash;
+
+use rustc_hash::FxHashMap;
+
+use super::{Map, Coord};
+
+/// The sites which influence the new value of a site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// the four orthogonal neighbors
+    VonNeumann,
+    /// the eight orthogonal and diagonal neighbors
+    Moore,
+    /// the 3x3 block centered on the site, including the site itself
+    Block,
+}
+
+impl Neighborhood {
+    /// Offsets of the neighborhood in row major order.
+    pub fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Neighborhood::VonNeumann => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Neighborhood::Moore => &[
+                (-1, -1), (0, -1), (1, -1),
+                (-1, 0), (1, 0),
+                (-1, 1), (0, 1), (1, 1),
+            ],
+            Neighborhood::Block => &[
+                (-1, -1), (0, -1), (1, -1),
+                (-1, 0), (0, 0), (1, 0),
+                (-1, 1), (0, 1), (1, 1),
+            ],
+        }
+    }
+}
+
+/// What a neighborhood sees beyond the edges of a `Map`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Boundary<T> {
+    /// all sites outside have this value
+    Constant(T),
+    /// the map wraps around like a torus
+    Periodic,
+}
+
+impl<T: Clone> Map<T> {
+    fn get_with_boundary(&self, x: isize, y: isize, boundary: &Boundary<T>) -> T {
+        let (w, h) = (self.width as isize, self.height as isize);
+        match boundary {
+            Boundary::Periodic => self[(x.rem_euclid(w) as usize, y.rem_euclid(h) as usize)].clone(),
+            Boundary::Constant(_) if x >= 0 && x < w && y >= 0 && y < h => self[(x as usize, y as usize)].clone(),
+            Boundary::Constant(value) => value.clone(),
+        }
+    }
+
+    /// Values of the neighborhood of `site` in row major order.
+    pub fn neighborhood<'a>(&'a self, site: Coord, neighborhood: Neighborhood, boundary: &'a Boundary<T>) -> impl Iterator<Item=T> + 'a {
+        let (x, y) = (site.0 as isize, site.1 as isize);
+        neighborhood.offsets().iter()
+            .map(move |(dx, dy)| self.get_with_boundary(x + dx, y + dy, boundary))
+    }
+
+    /// Synchronous update: the new value of every site is calculated by `rule`
+    /// from its old value and the old values of its neighborhood.
+    pub fn step_synchronous<F>(&self, neighborhood: Neighborhood, boundary: &Boundary<T>, rule: F) -> Map<T>
+        where F: Fn(&T, &[T]) -> T
+    {
+        let mut buffer = Vec::with_capacity(9);
+        let values = (0..self.height).map(|y| {
+            (0..self.width).map(|x| {
+                buffer.clear();
+                buffer.extend(self.neighborhood((x, y), neighborhood, boundary));
+                rule(&self[(x, y)], &buffer)
+            }).collect()
+        }).collect();
+
+        Map {
+            width: self.width,
+            height: self.height,
+            values,
+        }
+    }
+
+    /// Asynchronous update: the sites are updated in place in the given order,
+    /// such that later sites already see the new values of earlier sites.
+    ///
+    /// Returns whether any site changed.
+    pub fn step_asynchronous<F>(&mut self, order: impl IntoIterator<Item=Coord>, neighborhood: Neighborhood, boundary: &Boundary<T>, rule: F) -> bool
+        where F: Fn(&T, &[T]) -> T, T: PartialEq
+    {
+        let mut changed = false;
+        let mut buffer = Vec::with_capacity(9);
+        for site in order {
+            buffer.clear();
+            buffer.extend(self.neighborhood(site, neighborhood, boundary));
+            let new = rule(&self[site], &buffer);
+            if new != self[site] {
+                self[site] = new;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// All sites in row major order, the default order of asynchronous updates.
+    pub fn sites(&self) -> impl Iterator<Item=Coord> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+}
+
+/// An infinite grid, which has the value `background` everywhere outside of `map`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InfiniteMap<T> {
+    pub map: Map<T>,
+    pub background: T,
+}
+
+impl<T: Clone> InfiniteMap<T> {
+    /// Synchronous update, the finite part grows by one site in every direction.
+    pub fn step_synchronous<F>(&self, neighborhood: Neighborhood, rule: F) -> InfiniteMap<T>
+        where F: Fn(&T, &[T]) -> T
+    {
+        let boundary = Boundary::Constant(self.background.clone());
+        let mut buffer = Vec::with_capacity(9);
+        let values = (0..self.map.height + 2).map(|y| {
+            (0..self.map.width + 2).map(|x| {
+                let (x, y) = (x as isize - 1, y as isize - 1);
+                buffer.clear();
+                buffer.extend(neighborhood.offsets().iter()
+                    .map(|(dx, dy)| self.map.get_with_boundary(x + dx, y + dy, &boundary)));
+                rule(&self.map.get_with_boundary(x, y, &boundary), &buffer)
+            }).collect()
+        }).collect();
+
+        let background_neighborhood = vec![self.background.clone(); neighborhood.offsets().len()];
+
+        InfiniteMap {
+            map: Map {
+                width: self.map.width + 2,
+                height: self.map.height + 2,
+                values,
+            },
+            background: rule(&self.background, &background_neighborhood),
+        }
+    }
+}
+
+/// How an automaton was stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    /// the given number of steps was performed
+    Steps(usize),
+    /// the condition was fulfilled after the given number of steps
+    Condition(usize),
+    /// the state did not change in the given step anymore
+    Fixpoint(usize),
+    /// the state after `start + period` steps is the same as after `start` steps
+    Cycle { start: usize, period: usize },
+}
+
+impl Termination {
+    /// Number of steps performed until termination.
+    pub fn steps(&self) -> usize {
+        match *self {
+            Termination::Steps(n) | Termination::Condition(n) | Termination::Fixpoint(n) => n,
+            Termination::Cycle { start, period } => start + period,
+        }
+    }
+}
+
+/// Drives a step function until a stop condition is reached.
+///
+/// The step function advances the state by one step and returns whether the
+/// state changed. The observer is called with the number of performed steps
+/// and the state after every step, e.g., for visualization.
+pub struct Automaton<S, F, O = fn(usize, &S)> {
+    pub state: S,
+    pub steps: usize,
+    step: F,
+    observer: O,
+}
+
+impl<S, F: FnMut(&mut S) -> bool> Automaton<S, F> {
+    pub fn new(state: S, step: F) -> Automaton<S, F> {
+        Automaton {
+            state,
+            steps: 0,
+            step,
+            observer: |_, _| (),
+        }
+    }
+}
+
+impl<S, F: FnMut(&mut S) -> bool, O: FnMut(usize, &S)> Automaton<S, F, O> {
+    pub fn with_observer<P: FnMut(usize, &S)>(self, observer: P) -> Automaton<S, F, P> {
+        Automaton {
+            state: self.state,
+            steps: self.steps,
+            step: self.step,
+            observer,
+        }
+    }
+
+    /// Performs a single step and returns whether the state changed.
+    pub fn step(&mut self) -> bool {
+        let changed = (self.step)(&mut self.state);
+        self.steps += 1;
+        (self.observer)(self.steps, &self.state);
+        changed
+    }
+
+    pub fn run(&mut self, n: usize) -> Termination {
+        for _ in 0..n {
+            self.step();
+        }
+        Termination::Steps(self.steps)
+    }
+
+    /// Steps until the `condition` holds for the state after a step.
+    pub fn run_until(&mut self, condition: impl Fn(&S) -> bool) -> Termination {
+        loop {
+            self.step();
+            if condition(&self.state) {
+                return Termination::Condition(self.steps)
+            }
+        }
+    }
+
+    /// Steps until a step does not change the state anymore.
+    pub fn run_until_fixpoint(&mut self) -> Termination {
+        while self.step() {}
+        Termination::Fixpoint(self.steps)
+    }
+
+    /// Steps until a state repeats. This includes fixpoints, i.e., cycles of period 1.
+    pub fn run_until_cycle(&mut self) -> Termination
+        where S: Clone + Hash + Eq
+    {
+        let mut seen: FxHashMap<S, usize> = FxHashMap::default();
+        seen.insert(self.state.clone(), self.steps);
+        loop {
+            self.step();
+            if let Some(&start) = seen.get(&self.state) {
+                return Termination::Cycle { start, period: self.steps - start }
+            }
+            seen.insert(self.state.clone(), self.steps);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(alive: &bool, neighbors: &[bool]) -> bool {
+        let n = neighbors.iter().filter(|&&b| b).count();
+        n == 3 || (*alive && n == 2)
+    }
+
+    fn parse(input: &str) -> Map<bool> {
+        let map: Map<char> = input.parse().unwrap();
+        Map {
+            width: map.width,
+            height: map.height,
+            values: map.values.iter().map(|row| row.iter().map(|&c| c == '#').collect()).collect(),
+        }
+    }
+
+    #[test]
+    fn blinker() {
+        let map = parse(r"
+            .....
+            ..#..
+            ..#..
+            ..#..
+            .....
+        ");
+
+        let mut automaton = Automaton::new(map.clone(), |m: &mut Map<bool>| {
+            let next = m.step_synchronous(Neighborhood::Moore, &Boundary::Constant(false), life);
+            let changed = next != *m;
+            *m = next;
+            changed
+        });
+        assert_eq!(automaton.run_until_cycle(), Termination::Cycle { start: 0, period: 2 });
+        assert_eq!(automaton.state, map);
+    }
+
+    #[test]
+    fn glider_on_torus() {
+        let map = parse(r"
+            .#...
+            ..#..
+            ###..
+            .....
+            .....
+        ");
+
+        let mut observed = Vec::new();
+        let mut automaton = Automaton::new(map, |m: &mut Map<bool>| {
+            *m = m.step_synchronous(Neighborhood::Moore, &Boundary::Periodic, life);
+            true
+        }).with_observer(|n, m: &Map<bool>| observed.push((n, m.values.iter().flatten().filter(|&&b| b).count())));
+
+        // a glider travels one site diagonally every 4 steps
+        assert_eq!(automaton.run_until_cycle(), Termination::Cycle { start: 0, period: 20 });
+        assert!(observed.iter().all(|&(_, alive)| alive == 5));
+    }
+
+    #[test]
+    fn asynchronous() {
+        // spreading to the right happens in one sweep, if the order is left to right
+        let mut map = parse("#....");
+        let spread = |c: &bool, n: &[bool]| *c || n[1];
+        let order = map.sites().collect::<Vec<_>>();
+        assert!(map.step_asynchronous(order, Neighborhood::VonNeumann, &Boundary::Constant(false), spread));
+        assert!(map.values[0].iter().all(|&b| b));
+
+        let mut map = parse("#....");
+        let next = map.step_synchronous(Neighborhood::VonNeumann, &Boundary::Constant(false), spread);
+        assert_eq!(next.values[0], vec![true, true, false, false, false]);
+
+        let order = map.sites().collect::<Vec<_>>().into_iter().rev();
+        map.step_asynchronous(order, Neighborhood::VonNeumann, &Boundary::Constant(false), spread);
+        assert_eq!(next, map);
+    }
+
+    #[test]
+    fn infinite() {
+        let map = InfiniteMap {
+            map: parse("#"),
+            background: false,
+        };
+        // every site with a living neighbor becomes alive
+        let next = map.step_synchronous(Neighborhood::Block, |_, n| n.iter().any(|&b| b));
+        assert_eq!(next.map.width, 3);
+        assert!(next.map.values.iter().flatten().all(|&b| b));
+        assert!(!next.background);
+
+        let inverted = map.step_synchronous(Neighborhood::VonNeumann, |&c, _| !c);
+        assert_eq!(inverted.map, parse(r"
+            ###
+            #.#
+            ###
+        "));
+        assert!(inverted.background);
+    }
+}
@@ -0,0 +1,453 @@
+use core::fmt;
+use std::{str::FromStr, iter, ops::{Index, BitAnd, BitOr, BitXor, Not}};
+
+use rustc_hash::FxHashMap;
+
+use super::{AdventError, Map, Coord, shortest_path::Neighborful, automaton::{Boundary, Neighborhood}};
+
+const BITS: usize = u64::BITS as usize;
+
+/// A two dimensional grid of booleans, packed into 64 sites per word.
+///
+/// Every row starts at a new word, such that whole rows can be shifted and
+/// combined with bitwise operations, which updates 64 sites at once.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    pub width: usize,
+    pub height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        let words_per_row = width.div_ceil(BITS);
+        BitGrid {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: bool) -> BitGrid {
+        let mut grid = BitGrid::new(width, height);
+        if value {
+            for y in 0..height {
+                grid.row_mut(y).copy_from_slice(&full_row(width));
+            }
+        }
+        grid
+    }
+
+    pub fn get(&self, coordinate: Coord) -> bool {
+        let (x, y) = coordinate;
+        assert!(x < self.width && y < self.height);
+        self.row(y)[x / BITS] >> (x % BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, coordinate: Coord, value: bool) {
+        let (x, y) = coordinate;
+        assert!(x < self.width && y < self.height);
+        let word = &mut self.row_mut(y)[x / BITS];
+        if value {
+            *word |= 1 << (x % BITS);
+        } else {
+            *word &= !(1 << (x % BITS));
+        }
+    }
+
+    /// The words of a row, site `x` is bit `x % 64` of word `x / 64`.
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&w| w != 0)
+    }
+
+    pub fn find_one(&self, item: bool) -> Option<Coord> {
+        self.sites().find(|&c| self.get(c) == item)
+    }
+
+    pub fn find_all(&self, item: bool) -> Vec<Coord> {
+        self.sites().filter(|&c| self.get(c) == item).collect()
+    }
+
+    /// All sites in row major order.
+    pub fn sites(&self) -> impl Iterator<Item=Coord> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// The grid of the values of the neighbor at offset `(dx, dy)` of every
+    /// site, i.e., the site `(x, y)` of the result has the value of `(x + dx, y + dy)`.
+    pub fn offset(&self, dx: isize, dy: isize, boundary: &Boundary<bool>) -> BitGrid {
+        let mut out = BitGrid::new(self.width, self.height);
+        if self.width == 0 {
+            return out
+        }
+        let (w, h) = (self.width as isize, self.height as isize);
+        let outside = match boundary {
+            Boundary::Constant(true) => full_row(self.width),
+            _ => vec![0; self.words_per_row],
+        };
+
+        for y in 0..self.height {
+            let source_y = y as isize + dy;
+            let source = match boundary {
+                Boundary::Periodic => self.row(source_y.rem_euclid(h) as usize),
+                Boundary::Constant(_) if source_y < 0 || source_y >= h => &outside,
+                Boundary::Constant(_) => self.row(source_y as usize),
+            };
+
+            let row = match boundary {
+                Boundary::Periodic => {
+                    let dx = dx.rem_euclid(w);
+                    or(&shift_row(source, self.width, dx), &shift_row(source, self.width, dx - w))
+                },
+                Boundary::Constant(false) => shift_row(source, self.width, dx),
+                Boundary::Constant(true) => {
+                    let vacated = shift_row(&full_row(self.width), self.width, dx);
+                    let vacated: Vec<u64> = vacated.iter().zip(&outside).map(|(v, o)| !v & o).collect();
+                    or(&shift_row(source, self.width, dx), &vacated)
+                },
+            };
+            out.row_mut(y).copy_from_slice(&row);
+        }
+
+        out
+    }
+
+    /// A larger grid with a border of `n` sites with the given value on each side.
+    pub fn padded(&self, n: usize, value: bool) -> BitGrid {
+        let mut out = BitGrid::filled(self.width + 2 * n, self.height + 2 * n, value);
+        for (x, y) in self.sites() {
+            out.set((x + n, y + n), self.get((x, y)));
+        }
+        out
+    }
+
+    /// Counts the set sites in the neighborhood of every site.
+    ///
+    /// The counts are returned bit sliced, i.e., the `k`-th grid contains the
+    /// `k`-th binary digit of the count of every site.
+    pub fn neighbor_counts(&self, neighborhood: Neighborhood, boundary: &Boundary<bool>) -> Vec<BitGrid> {
+        let offsets = neighborhood.offsets();
+        let num_digits = (usize::BITS - offsets.len().leading_zeros()) as usize;
+        let mut digits = vec![BitGrid::new(self.width, self.height); num_digits];
+
+        for &(dx, dy) in offsets {
+            // ripple carry addition of a single bit to every counter
+            let mut carry = self.offset(dx, dy, boundary);
+            for digit in digits.iter_mut() {
+                let next_carry = &*digit & &carry;
+                *digit = &*digit ^ &carry;
+                carry = next_carry;
+            }
+        }
+        digits
+    }
+
+    /// Evaluates a lookup table bit parallel for every site.
+    ///
+    /// The index into `table` of a site is formed by the values of the site
+    /// in `inputs`, where the first input is the most significant bit.
+    pub fn lookup(inputs: &[BitGrid], table: &[bool]) -> BitGrid {
+        assert_eq!(table.len(), 1 << inputs.len());
+        let (width, height) = (inputs[0].width, inputs[0].height);
+        assert!(inputs.iter().all(|i| i.width == width && i.height == height));
+
+        // the table as a reduced decision diagram, children are before their parents
+        let mut nodes = Vec::new();
+        let mut known = FxHashMap::default();
+        let root = decision_node(table, 0, &mut nodes, &mut known);
+
+        let mut out = BitGrid::new(width, height);
+        let mut values = vec![0; nodes.len()];
+        let mask = full_row(width);
+        for (i, word) in out.words.iter_mut().enumerate() {
+            for (n, node) in nodes.iter().enumerate() {
+                values[n] = match *node {
+                    Decision::Constant(false) => 0,
+                    Decision::Constant(true) => !0,
+                    Decision::Branch { input, low, high } => {
+                        let selector = inputs[input].words[i];
+                        (selector & values[high]) | (!selector & values[low])
+                    }
+                };
+            }
+            *word = values[root] & mask[i % out.words_per_row];
+        }
+
+        out
+    }
+}
+
+enum Decision {
+    Constant(bool),
+    Branch { input: usize, low: usize, high: usize },
+}
+
+fn decision_node(table: &[bool], input: usize, nodes: &mut Vec<Decision>, known: &mut FxHashMap<Vec<bool>, usize>) -> usize {
+    if let Some(&n) = known.get(table) {
+        return n
+    }
+
+    let node = if table.iter().all(|&b| b == table[0]) {
+        Decision::Constant(table[0])
+    } else {
+        let (low, high) = table.split_at(table.len() / 2);
+        let low = decision_node(low, input + 1, nodes, known);
+        let high = decision_node(high, input + 1, nodes, known);
+        Decision::Branch { input, low, high }
+    };
+
+    nodes.push(node);
+    known.insert(table.to_vec(), nodes.len() - 1);
+    nodes.len() - 1
+}
+
+/// A row with all sites set.
+fn full_row(width: usize) -> Vec<u64> {
+    let mut row = vec![!0; width.div_ceil(BITS)];
+    if !width.is_multiple_of(BITS) {
+        *row.last_mut().unwrap() = (1 << (width % BITS)) - 1;
+    }
+    row
+}
+
+fn or(a: &[u64], b: &[u64]) -> Vec<u64> {
+    a.iter().zip(b).map(|(x, y)| x | y).collect()
+}
+
+/// Site `x` of the result is site `x + k` of `row`, or unset if that is outside.
+fn shift_row(row: &[u64], width: usize, k: isize) -> Vec<u64> {
+    let n = row.len();
+    let get = |i: isize| if i >= 0 && (i as usize) < n {row[i as usize]} else {0};
+    let (q, r) = (k.div_euclid(BITS as isize), k.rem_euclid(BITS as isize) as u32);
+
+    let mut out: Vec<u64> = (0..n as isize).map(|i| {
+        let lower = get(i + q) >> r;
+        let upper = if r == 0 {0} else {get(i + q + 1) << (BITS as u32 - r)};
+        lower | upper
+    }).collect();
+
+    // bits beyond the width have to stay unset
+    if let Some(last) = out.last_mut() {
+        if !width.is_multiple_of(BITS) {
+            *last &= (1 << (width % BITS)) - 1;
+        }
+    }
+    out
+}
+
+macro_rules! impl_bitwise_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl $trait for &BitGrid {
+            type Output = BitGrid;
+
+            fn $method(self, rhs: Self) -> Self::Output {
+                assert!(self.width == rhs.width && self.height == rhs.height);
+                BitGrid {
+                    width: self.width,
+                    height: self.height,
+                    words_per_row: self.words_per_row,
+                    words: self.words.iter().zip(&rhs.words).map(|(a, b)| a $op b).collect(),
+                }
+            }
+        }
+    };
+}
+
+impl_bitwise_op!(BitAnd, bitand, &);
+impl_bitwise_op!(BitOr, bitor, |);
+impl_bitwise_op!(BitXor, bitxor, ^);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> Self::Output {
+        let mask = full_row(self.width);
+        BitGrid {
+            width: self.width,
+            height: self.height,
+            words_per_row: self.words_per_row,
+            words: self.words.iter().enumerate()
+                .map(|(i, w)| !w & mask[i % self.words_per_row])
+                .collect(),
+        }
+    }
+}
+
+impl Index<Coord> for BitGrid {
+    type Output = bool;
+
+    fn index(&self, coordinate: Coord) -> &Self::Output {
+        if self.get(coordinate) {&true} else {&false}
+    }
+}
+
+impl From<&Map<bool>> for BitGrid {
+    fn from(map: &Map<bool>) -> Self {
+        let mut grid = BitGrid::new(map.width, map.height);
+        for y in 0..map.height {
+            for x in 0..map.width {
+                grid.set((x, y), map[(x, y)]);
+            }
+        }
+        grid
+    }
+}
+
+impl From<&BitGrid> for Map<bool> {
+    fn from(grid: &BitGrid) -> Self {
+        Map {
+            width: grid.width,
+            height: grid.height,
+            values: (0..grid.height).map(|y| (0..grid.width).map(|x| grid.get((x, y))).collect()).collect(),
+        }
+    }
+}
+
+impl fmt::Display for BitGrid
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                write!(f, "{}", if self.get((x, y)) {'#'} else {'.'})?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a grid of `#` (set) and `.` (unset).
+impl FromStr for BitGrid
+{
+    type Err = AdventError;
+
+    fn from_str(input: &str) -> Result<Self, AdventError> {
+        let map: Map<char> = input.parse()?;
+        let mut grid = BitGrid::new(map.width, map.height);
+        for y in 0..map.height {
+            for x in 0..map.width {
+                match map[(x, y)] {
+                    '#' => grid.set((x, y), true),
+                    '.' => (),
+                    c => return Err(AdventError::UnexpectedElement { found: c.to_string(), expected: &["#", "."] }),
+                }
+            }
+        }
+        Ok(grid)
+    }
+}
+
+impl Neighborful<Coord> for &BitGrid {
+    fn neighbors(&self, coordinate: Coord) -> impl Iterator<Item=Coord> {
+        let (x, y) = coordinate;
+        iter::once(
+            if y == 0 {None} else {Some((x, y-1))}
+        ).chain(iter::once(
+            if y >= self.height - 1  {None} else {Some((x, y+1))},
+        )).chain(iter::once(
+            if x == 0 {None} else {Some((x-1, y))},
+        )).chain(iter::once(
+            if x >= self.width - 1 {None} else {Some((x+1, y))},
+        )).flatten()
+    }
+
+    fn distance(c1: Coord, c2: Coord) -> usize {
+        c1.0.abs_diff(c2.0) + c1.1.abs_diff(c2.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(alive: &bool, neighbors: &[bool]) -> bool {
+        let n = neighbors.iter().filter(|&&b| b).count();
+        n == 3 || (*alive && n == 2)
+    }
+
+    #[test]
+    fn offsets() {
+        // wider than a word, to test the carries between words
+        let mut grid = BitGrid::new(130, 3);
+        for x in [0, 63, 64, 127, 129] {
+            grid.set((x, 1), true);
+        }
+        let map = Map::from(&grid);
+
+        for boundary in [Boundary::Constant(false), Boundary::Constant(true), Boundary::Periodic] {
+            for &(dx, dy) in Neighborhood::Block.offsets().iter().chain(&[(65, 0), (-70, 1)]) {
+                let shifted = grid.offset(dx, dy, &boundary);
+                for (x, y) in grid.sites() {
+                    let (i, j) = (x as isize + dx, y as isize + dy);
+                    let expected = match boundary {
+                        Boundary::Periodic => map[(i.rem_euclid(130) as usize, j.rem_euclid(3) as usize)],
+                        Boundary::Constant(c) if !(0..130).contains(&i) || !(0..3).contains(&j) => c,
+                        Boundary::Constant(_) => map[(i as usize, j as usize)],
+                    };
+                    assert_eq!(shifted[(x, y)], expected, "{boundary:?} {dx} {dy} at {x} {y}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn game_of_life() {
+        let grid: BitGrid = r"
+            .#.......#
+            ..#.....#.
+            ###.##..#.
+            ....##....
+        ".parse().unwrap();
+        let boundary = Boundary::Periodic;
+
+        let counts = grid.neighbor_counts(Neighborhood::Moore, &boundary);
+        assert_eq!(counts.len(), 4);
+        // 2 neighbors are binary 0010 and 3 neighbors are binary 0011
+        let two_or_three = &counts[1] & &!&(&counts[2] | &counts[3]);
+        let three = &two_or_three & &counts[0];
+        let next = &three | &(&grid & &two_or_three);
+
+        let expected = Map::from(&grid).step_synchronous(Neighborhood::Moore, &boundary, life);
+        assert_eq!(Map::from(&next), expected);
+    }
+
+    #[test]
+    fn lookup_table() {
+        let grid: BitGrid = r"
+            #..#.
+            #....
+            ##..#
+            ..#..
+            ..###
+        ".parse().unwrap();
+        let boundary = Boundary::Constant(false);
+        // the table is true if the index has an odd number of set bits
+        let table: Vec<bool> = (0..512usize).map(|i| i.count_ones() % 2 == 1).collect();
+
+        let inputs: Vec<BitGrid> = Neighborhood::Block.offsets().iter()
+            .map(|&(dx, dy)| grid.offset(dx, dy, &boundary))
+            .collect();
+        let result = BitGrid::lookup(&inputs, &table);
+
+        let expected = Map::from(&grid).step_synchronous(Neighborhood::Block, &boundary, |_, block| {
+            block.iter().filter(|&&b| b).count() % 2 == 1
+        });
+        assert_eq!(Map::from(&result), expected);
+        assert_eq!(result.to_string().parse::<BitGrid>().unwrap(), result);
+    }
+}
@@ -0,0 +1,295 @@
+use std::ops::Range;
+
+/// Axis aligned box of integer sites in `N` dimensions, half-open along every axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AABox<const N: usize> {
+    pub min: [isize; N],
+    /// exclusive
+    pub max: [isize; N],
+}
+
+impl<const N: usize> AABox<N> {
+    pub fn new(min: [isize; N], max: [isize; N]) -> AABox<N> {
+        AABox { min, max }
+    }
+
+    /// A box including the sites at `max`.
+    pub fn from_inclusive(min: [isize; N], max: [isize; N]) -> AABox<N> {
+        AABox { min, max: max.map(|m| m + 1) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..N).any(|d| self.min[d] >= self.max[d])
+    }
+
+    /// Number of sites in the box.
+    pub fn volume(&self) -> usize {
+        (0..N).map(|d| self.max[d].saturating_sub(self.min[d]).max(0) as usize).product()
+    }
+
+    pub fn contains_point(&self, point: [isize; N]) -> bool {
+        (0..N).all(|d| self.min[d] <= point[d] && point[d] < self.max[d])
+    }
+
+    /// Whether `other` lies completely within this box.
+    pub fn contains(&self, other: &AABox<N>) -> bool {
+        other.is_empty() || (0..N).all(|d| self.min[d] <= other.min[d] && other.max[d] <= self.max[d])
+    }
+
+    pub fn intersection(&self, other: &AABox<N>) -> Option<AABox<N>> {
+        let cut = AABox {
+            min: std::array::from_fn(|d| self.min[d].max(other.min[d])),
+            max: std::array::from_fn(|d| self.max[d].min(other.max[d])),
+        };
+        (!cut.is_empty()).then_some(cut)
+    }
+
+    /// The sites of this box, which are not in `other`, as at most `2 N` disjoint boxes.
+    pub fn subtract(&self, other: &AABox<N>) -> Vec<AABox<N>> {
+        let Some(cut) = self.intersection(other) else {
+            return vec![*self]
+        };
+
+        // peel off the slabs below and above the cut along one axis after the other
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for d in 0..N {
+            if rest.min[d] < cut.min[d] {
+                let mut slab = rest;
+                slab.max[d] = cut.min[d];
+                pieces.push(slab);
+                rest.min[d] = cut.min[d];
+            }
+            if cut.max[d] < rest.max[d] {
+                let mut slab = rest;
+                slab.min[d] = cut.max[d];
+                pieces.push(slab);
+                rest.max[d] = cut.max[d];
+            }
+        }
+        pieces
+    }
+}
+
+/// Union of boxes, stored as disjoint boxes such that the volume is exact.
+///
+/// ```
+/// use aoc2021::utils::boxes::{AABox, BoxSet};
+///
+/// let mut set = BoxSet::new();
+/// set.insert(AABox::new([0, 0], [4, 4]));
+/// set.insert(AABox::new([2, 2], [6, 6]));
+/// set.remove(&AABox::new([3, 0], [4, 10]));
+/// assert_eq!(set.volume(), 16 + 16 - 4 - 6);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BoxSet<const N: usize> {
+    boxes: Vec<AABox<N>>,
+}
+
+impl<const N: usize> BoxSet<N> {
+    pub fn new() -> BoxSet<N> {
+        BoxSet { boxes: Vec::new() }
+    }
+
+    /// Disjoint boxes covering the set.
+    pub fn boxes(&self) -> &[AABox<N>] {
+        &self.boxes
+    }
+
+    pub fn insert(&mut self, b: AABox<N>) {
+        // removing the overlap first keeps the boxes disjoint
+        self.remove(&b);
+        if !b.is_empty() {
+            self.boxes.push(b);
+        }
+    }
+
+    pub fn remove(&mut self, b: &AABox<N>) {
+        self.boxes = self.boxes.iter()
+            .flat_map(|existing| existing.subtract(b))
+            .collect();
+    }
+
+    pub fn union(&self, other: &BoxSet<N>) -> BoxSet<N> {
+        let mut set = self.clone();
+        for &b in &other.boxes {
+            set.insert(b);
+        }
+        set
+    }
+
+    pub fn difference(&self, other: &BoxSet<N>) -> BoxSet<N> {
+        let mut set = self.clone();
+        for b in &other.boxes {
+            set.remove(b);
+        }
+        set
+    }
+
+    /// The part of the set within `region`.
+    pub fn clip(&self, region: &AABox<N>) -> BoxSet<N> {
+        BoxSet {
+            boxes: self.boxes.iter().filter_map(|b| b.intersection(region)).collect()
+        }
+    }
+
+    pub fn contains_point(&self, point: [isize; N]) -> bool {
+        self.boxes.iter().any(|b| b.contains_point(point))
+    }
+
+    pub fn volume(&self) -> usize {
+        self.boxes.iter().map(|b| b.volume()).sum()
+    }
+}
+
+/// Alternative to `BoxSet` for a known set of boxes: the boundaries of all
+/// boxes divide space into a grid of cells, which are either completely
+/// inside or outside of the set.
+///
+/// The number of cells grows with the `N`-th power of the number of boxes,
+/// so this is only suitable for few boxes, but every operation is cheap.
+#[derive(Debug, Clone)]
+pub struct CompressedGrid<const N: usize> {
+    /// sorted boundaries along every axis
+    coords: [Vec<isize>; N],
+    cells: Vec<bool>,
+}
+
+impl<const N: usize> CompressedGrid<N> {
+    /// An empty set, which can only be modified by the given boxes.
+    pub fn new<'a>(boxes: impl IntoIterator<Item=&'a AABox<N>> + Clone) -> CompressedGrid<N> {
+        let coords: [Vec<isize>; N] = std::array::from_fn(|d| {
+            let mut c: Vec<isize> = boxes.clone().into_iter()
+                .filter(|b| !b.is_empty())
+                .flat_map(|b| [b.min[d], b.max[d]])
+                .collect();
+            c.sort_unstable();
+            c.dedup();
+            c
+        });
+        let size = coords.iter().map(|c| c.len().saturating_sub(1)).product();
+        CompressedGrid { coords, cells: vec![false; size] }
+    }
+
+    fn cell_range(&self, b: &AABox<N>) -> [Range<usize>; N] {
+        std::array::from_fn(|d| {
+            let position = |x| self.coords[d].binary_search(&x)
+                .expect("only boxes given at construction can be used");
+            position(b.min[d])..position(b.max[d])
+        })
+    }
+
+    /// Calls `f` with the flat index and the multi index of every cell in `ranges`.
+    fn for_each_cell(&self, ranges: [Range<usize>; N], mut f: impl FnMut(usize, [usize; N])) {
+        if ranges.iter().any(|r| r.is_empty()) {
+            return
+        }
+        let mut index: [usize; N] = std::array::from_fn(|d| ranges[d].start);
+        loop {
+            let flat = (0..N).fold(0, |flat, d| flat * (self.coords[d].len() - 1) + index[d]);
+            f(flat, index);
+
+            // odometer: increment the last axis and carry over
+            let mut d = N;
+            loop {
+                if d == 0 {
+                    return
+                }
+                d -= 1;
+                index[d] += 1;
+                if index[d] < ranges[d].end {
+                    break
+                }
+                index[d] = ranges[d].start;
+            }
+        }
+    }
+
+    fn set(&mut self, b: &AABox<N>, value: bool) {
+        if b.is_empty() {
+            return
+        }
+        let mut cells = std::mem::take(&mut self.cells);
+        self.for_each_cell(self.cell_range(b), |i, _| cells[i] = value);
+        self.cells = cells;
+    }
+
+    pub fn insert(&mut self, b: &AABox<N>) {
+        self.set(b, true);
+    }
+
+    pub fn remove(&mut self, b: &AABox<N>) {
+        self.set(b, false);
+    }
+
+    pub fn volume(&self) -> usize {
+        let all = std::array::from_fn(|d| 0..self.coords[d].len().saturating_sub(1));
+        let mut volume = 0;
+        self.for_each_cell(all, |i, index| {
+            if self.cells[i] {
+                volume += (0..N)
+                    .map(|d| (self.coords[d][index[d] + 1] - self.coords[d][index[d]]) as usize)
+                    .product::<usize>();
+            }
+        });
+        volume
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subtraction() {
+        let a = AABox::new([0, 0, 0], [3, 3, 3]);
+        let hole = AABox::new([1, 1, 1], [2, 2, 2]);
+        let pieces = a.subtract(&hole);
+        assert_eq!(pieces.len(), 6);
+        assert_eq!(pieces.iter().map(|p| p.volume()).sum::<usize>(), 26);
+        for (i, p) in pieces.iter().enumerate() {
+            assert!(p.intersection(&hole).is_none());
+            assert!(pieces[i + 1..].iter().all(|q| p.intersection(q).is_none()));
+        }
+
+        assert_eq!(a.subtract(&AABox::new([5, 5, 5], [6, 6, 6])), vec![a]);
+        assert!(a.subtract(&AABox::new([-1, -1, -1], [4, 4, 4])).is_empty());
+        assert!(a.contains(&hole) && !hole.contains(&a));
+    }
+
+    #[test]
+    fn backends_agree() {
+        let boxes = [
+            (AABox::new([0, 0, 0], [10, 10, 10]), true),
+            (AABox::new([5, 5, 5], [15, 15, 15]), true),
+            (AABox::new([-3, 2, 4], [7, 8, 12]), false),
+            (AABox::new([2, 2, 2], [4, 4, 4]), true),
+            (AABox::new([9, 0, 0], [9, 10, 10]), true),
+        ];
+
+        let mut set = BoxSet::new();
+        let mut grid = CompressedGrid::new(boxes.iter().map(|(b, _)| b));
+        for (b, on) in &boxes {
+            if *on {
+                set.insert(*b);
+                grid.insert(b);
+            } else {
+                set.remove(b);
+                grid.remove(b);
+            }
+        }
+
+        let brute = (-5..20).flat_map(|x| (-5..20).flat_map(move |y| (-5..20).map(move |z| [x, y, z])))
+            .filter(|&p| set.contains_point(p))
+            .count();
+        assert_eq!(set.volume(), brute);
+        assert_eq!(grid.volume(), brute);
+
+        let region = AABox::new([0, 0, 0], [5, 5, 5]);
+        // only the slab z = 4 of the removed box reaches into the region
+        assert_eq!(set.clip(&region).volume(), 125 - 5 * 3);
+        assert_eq!(set.difference(&set).volume(), 0);
+        assert_eq!(set.union(&set.clip(&region)).volume(), brute);
+    }
+}
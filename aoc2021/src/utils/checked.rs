@@ -0,0 +1,147 @@
+use super::AdventError;
+
+/// Numbers whose arithmetic reports overflow as an error instead of wrapping
+/// around in release builds.
+///
+/// With the feature `bigint`, this is also implemented for `BigUint` and
+/// `BigInt`, which never overflow, such that solvers generic over this trait
+/// can switch to arbitrary precision.
+pub trait Checked: Sized + Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn try_add(&self, other: &Self) -> Result<Self, AdventError>;
+    fn try_sub(&self, other: &Self) -> Result<Self, AdventError>;
+    fn try_mul(&self, other: &Self) -> Result<Self, AdventError>;
+}
+
+macro_rules! impl_checked {
+    ($($t:ty)*) => {$(
+        impl Checked for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn one() -> Self {
+                1
+            }
+
+            fn try_add(&self, other: &Self) -> Result<Self, AdventError> {
+                self.checked_add(*other).ok_or(AdventError::Overflow)
+            }
+
+            fn try_sub(&self, other: &Self) -> Result<Self, AdventError> {
+                self.checked_sub(*other).ok_or(AdventError::Overflow)
+            }
+
+            fn try_mul(&self, other: &Self) -> Result<Self, AdventError> {
+                self.checked_mul(*other).ok_or(AdventError::Overflow)
+            }
+        }
+    )*}
+}
+
+impl_checked!{u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize}
+
+#[cfg(feature = "bigint")]
+mod big {
+    use num_bigint::{BigInt, BigUint};
+
+    use super::{AdventError, Checked};
+
+    impl Checked for BigUint {
+        fn zero() -> Self {
+            BigUint::from(0u8)
+        }
+
+        fn one() -> Self {
+            BigUint::from(1u8)
+        }
+
+        fn try_add(&self, other: &Self) -> Result<Self, AdventError> {
+            Ok(self + other)
+        }
+
+        /// Fails for negative results, which `BigUint` cannot represent.
+        fn try_sub(&self, other: &Self) -> Result<Self, AdventError> {
+            if other > self {
+                return Err(AdventError::Overflow)
+            }
+            Ok(self - other)
+        }
+
+        fn try_mul(&self, other: &Self) -> Result<Self, AdventError> {
+            Ok(self * other)
+        }
+    }
+
+    impl Checked for BigInt {
+        fn zero() -> Self {
+            BigInt::from(0u8)
+        }
+
+        fn one() -> Self {
+            BigInt::from(1u8)
+        }
+
+        fn try_add(&self, other: &Self) -> Result<Self, AdventError> {
+            Ok(self + other)
+        }
+
+        fn try_sub(&self, other: &Self) -> Result<Self, AdventError> {
+            Ok(self - other)
+        }
+
+        fn try_mul(&self, other: &Self) -> Result<Self, AdventError> {
+            Ok(self * other)
+        }
+    }
+}
+
+/// Sum and product of iterators, which fail on overflow.
+///
+/// ```
+/// use aoc2021::utils::checked::CheckedIterator;
+///
+/// assert_eq!([200u8, 50].into_iter().checked_sum().unwrap(), 250);
+/// assert!([200u8, 60].into_iter().checked_sum().is_err());
+/// assert!([1usize << 40, 1 << 30].into_iter().checked_product().is_err());
+/// ```
+pub trait CheckedIterator: Iterator {
+    fn checked_sum(self) -> Result<Self::Item, AdventError>;
+    fn checked_product(self) -> Result<Self::Item, AdventError>;
+}
+
+impl<I: Iterator<Item = T>, T: Checked> CheckedIterator for I {
+    fn checked_sum(mut self) -> Result<T, AdventError> {
+        self.try_fold(T::zero(), |acc, x| acc.try_add(&x))
+    }
+
+    fn checked_product(mut self) -> Result<T, AdventError> {
+        self.try_fold(T::one(), |acc, x| acc.try_mul(&x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow() {
+        assert_eq!(usize::MAX.try_sub(&1).unwrap(), usize::MAX - 1);
+        assert!(matches!(usize::MAX.try_add(&1), Err(AdventError::Overflow)));
+        assert!(matches!(0u32.try_sub(&1), Err(AdventError::Overflow)));
+        assert!(matches!(i64::MIN.try_mul(&-1), Err(AdventError::Overflow)));
+        assert_eq!((1..=20u64).checked_product().unwrap(), 2432902008176640000);
+        assert!((1..=21u64).checked_product().is_err());
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn big() {
+        use num_bigint::BigUint;
+
+        let factorial = (1..=30u32).map(BigUint::from).checked_product().unwrap();
+        assert_eq!(factorial.to_string(), "265252859812191058636308480000000");
+        assert!(BigUint::zero().try_sub(&BigUint::one()).is_err());
+    }
+}
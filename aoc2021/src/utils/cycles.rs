@@ -0,0 +1,230 @@
+use std::hash::Hash;
+
+use rustc_hash::FxHashMap;
+
+use super::number_theory::{crt, lcm};
+
+/// Shape of the orbit of an iterated function: after `tail` steps the states
+/// repeat with the given `period`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub tail: usize,
+    pub period: usize,
+}
+
+/// Floyd's tortoise and hare, needs only two states at a time.
+pub fn floyd<S: PartialEq + Clone>(start: S, mut f: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = f(&start);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        let half = f(&hare);
+        hare = f(&half);
+    }
+
+    // the distance between hare and tortoise is a multiple of the period now
+    let mut tail = 0;
+    let mut tortoise = start;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        tail += 1;
+    }
+
+    let mut period = 1;
+    hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        period += 1;
+    }
+
+    Cycle { tail, period }
+}
+
+/// Brent's algorithm, needs only two states at a time and fewer evaluations than `floyd`.
+pub fn brent<S: PartialEq + Clone>(start: S, mut f: impl FnMut(&S) -> S) -> Cycle {
+    // find the period by searching in windows of increasing powers of two
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = f(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = f(&hare);
+        period += 1;
+    }
+
+    // a hare `period` steps ahead meets the tortoise at the start of the cycle
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..period {
+        hare = f(&hare);
+    }
+    let mut tail = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        tail += 1;
+    }
+
+    Cycle { tail, period }
+}
+
+/// The steps at which an orbit is in a goal state.
+///
+/// Finitely many hits in the tail and periodically repeating hits afterwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hits {
+    pub cycle: Cycle,
+    /// hits before the cycle, i.e., less than `cycle.tail`
+    pub tail_hits: Vec<usize>,
+    /// hits during the first pass through the cycle, i.e.,
+    /// in `cycle.tail..cycle.tail + cycle.period`
+    pub cycle_hits: Vec<usize>,
+}
+
+/// Detects the cycle of the orbit of `start` under `f` by remembering all
+/// states, and records at which steps the state fulfills `goal`.
+pub fn goal_steps<S>(start: S, mut f: impl FnMut(&S) -> S, mut goal: impl FnMut(&S) -> bool) -> Hits
+    where S: Hash + Eq + Clone
+{
+    let mut seen: FxHashMap<S, usize> = FxHashMap::default();
+    let mut hits = Vec::new();
+    let mut state = start;
+    let mut step = 0;
+    let tail = loop {
+        if let Some(&tail) = seen.get(&state) {
+            break tail
+        }
+        if goal(&state) {
+            hits.push(step);
+        }
+        let next = f(&state);
+        seen.insert(state, step);
+        state = next;
+        step += 1;
+    };
+
+    let cycle_hits = hits.split_off(hits.partition_point(|&h| h < tail));
+    Hits {
+        cycle: Cycle { tail, period: step - tail },
+        tail_hits: hits,
+        cycle_hits,
+    }
+}
+
+impl Hits {
+    /// Whether the orbit is in a goal state after `step` steps.
+    pub fn contains(&self, step: usize) -> bool {
+        let Cycle { tail, period } = self.cycle;
+        if step < tail {
+            self.tail_hits.binary_search(&step).is_ok()
+        } else {
+            self.cycle_hits.binary_search(&(tail + (step - tail) % period)).is_ok()
+        }
+    }
+
+    /// All hits in ascending order, infinitely many if the cycle contains a goal.
+    pub fn iter(&self) -> impl Iterator<Item=usize> + '_ {
+        let period = self.cycle.period;
+        let passes = if self.cycle_hits.is_empty() {0} else {usize::MAX};
+        let repeated = (0..passes).flat_map(move |n| self.cycle_hits.iter().map(move |h| h + n * period));
+        self.tail_hits.iter().cloned().chain(repeated)
+    }
+
+    pub fn first(&self) -> Option<usize> {
+        self.tail_hits.first().or(self.cycle_hits.first()).cloned()
+    }
+
+    /// The steps at which both orbits are in a goal state, e.g., of
+    /// independent machines running simultaneously.
+    ///
+    /// The periodic hits are combined by the chinese remainder theorem,
+    /// which also works for periods with common factors.
+    pub fn merge(&self, other: &Hits) -> Hits {
+        let tail = self.cycle.tail.max(other.cycle.tail);
+        let period = lcm(self.cycle.period, other.cycle.period);
+
+        let tail_hits = self.iter()
+            .take_while(|&h| h < tail)
+            .filter(|&h| other.contains(h))
+            .collect();
+
+        let mut cycle_hits: Vec<usize> = self.cycle_hits.iter()
+            .flat_map(|&a| other.cycle_hits.iter().map(move |&b| (a, b)))
+            .filter_map(|(a, b)| crt([(a, self.cycle.period), (b, other.cycle.period)]))
+            // the smallest solution which is not less than the combined tail
+            .map(|(r, m)| r + (tail.saturating_sub(r)).div_ceil(m) * m)
+            .collect();
+        cycle_hits.sort_unstable();
+
+        Hits {
+            cycle: Cycle { tail, period },
+            tail_hits,
+            cycle_hits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a rho shaped orbit: 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 2
+    fn rho(&n: &usize) -> usize {
+        if n == 6 {2} else {n + 1}
+    }
+
+    #[test]
+    fn detection() {
+        let expected = Cycle { tail: 2, period: 5 };
+        assert_eq!(floyd(0, rho), expected);
+        assert_eq!(brent(0, rho), expected);
+        assert_eq!(goal_steps(0, rho, |_| false).cycle, expected);
+
+        // a fixpoint is a cycle of period 1
+        let fix = |&n: &usize| n.saturating_sub(1);
+        assert_eq!(brent(3, fix), Cycle { tail: 3, period: 1 });
+        assert_eq!(floyd(3, fix), Cycle { tail: 3, period: 1 });
+    }
+
+    #[test]
+    fn hits() {
+        let hits = goal_steps(0, rho, |&n| n % 2 == 1);
+        assert_eq!(hits.tail_hits, vec![1]);
+        assert_eq!(hits.cycle_hits, vec![3, 5]);
+        assert_eq!(hits.iter().take(6).collect::<Vec<_>>(), vec![1, 3, 5, 8, 10, 13]);
+        assert!(hits.contains(10));
+        assert!(!hits.contains(11));
+        assert_eq!(hits.first(), Some(1));
+    }
+
+    #[test]
+    fn merging() {
+        // counters modulo 4 and 6, which hit at 1 mod 4 and 3 mod 6
+        let a = goal_steps(0, |&n: &usize| (n + 1) % 4, |&n| n == 1);
+        let b = goal_steps(0, |&n: &usize| (n + 1) % 6, |&n| n == 3);
+        let merged = a.merge(&b);
+        assert_eq!(merged.cycle, Cycle { tail: 0, period: 12 });
+        assert_eq!(merged.iter().take(3).collect::<Vec<_>>(), vec![9, 21, 33]);
+
+        // 0 mod 4 and 1 mod 6 never coincide
+        let c = goal_steps(0, |&n: &usize| (n + 1) % 6, |&n| n == 1);
+        let a = goal_steps(0, |&n: &usize| (n + 1) % 4, |&n| n == 0);
+        assert_eq!(a.merge(&c).first(), None);
+        assert_eq!(a.merge(&c).iter().next(), None);
+
+        // with tails, hits before the longer tail have to be checked directly
+        let d = goal_steps(0, rho, |&n| n == 1 || n == 4);
+        let e = goal_steps(0, |&n: &usize| (n + 1) % 2, |&n| n == 1);
+        let merged = d.merge(&e);
+        assert_eq!(merged.iter().take(4).collect::<Vec<_>>(), vec![1, 9, 19, 29]);
+        for n in 0..100 {
+            assert_eq!(merged.contains(n), d.contains(n) && e.contains(n));
+        }
+    }
+}
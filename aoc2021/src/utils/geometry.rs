@@ -0,0 +1,161 @@
+use std::cmp::Ordering;
+
+use super::{Point, Point2, Scalar, number_theory::gcd};
+
+/// z-component of the cross product, positive if `b` is counterclockwise of `a`
+/// (with the y-axis pointing up).
+pub fn cross<T: Scalar>(a: &Point<T, 2>, b: &Point<T, 2>) -> T {
+    a.x() * b.y() - a.y() * b.x()
+}
+
+/// Whether `c` is counterclockwise (`Greater`), clockwise (`Less`) or on the
+/// line through `a` and `b` (`Equal`).
+pub fn orientation<T: Scalar>(a: &Point<T, 2>, b: &Point<T, 2>, c: &Point<T, 2>) -> Ordering {
+    cross(&(b - a), &(c - a))
+        .partial_cmp(&T::ZERO)
+        .expect("coordinates have to be comparable")
+}
+
+/// Twice the signed area of the polygon with the vertices in order by the
+/// shoelace formula, positive for counterclockwise vertices. Twice the area of
+/// a lattice polygon is always an integer.
+///
+/// ```
+/// use aoc2021::utils::{Point2, geometry::double_area};
+///
+/// let triangle = [Point2::new(0, 0), Point2::new(4, 0), Point2::new(0, 3)];
+/// assert_eq!(double_area(&triangle), 12);
+/// ```
+pub fn double_area<T: Scalar>(vertices: &[Point<T, 2>]) -> T {
+    vertices.iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| cross(a, b))
+        .sum()
+}
+
+/// Number of lattice points on the boundary of the polygon.
+pub fn boundary_points(vertices: &[Point2]) -> usize {
+    vertices.iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| gcd(a.x().abs_diff(b.x()), a.y().abs_diff(b.y())))
+        .sum()
+}
+
+/// Number of lattice points strictly inside the simple polygon with the
+/// vertices in order, by Pick's theorem `A = I + B / 2 - 1`.
+///
+/// ```
+/// use aoc2021::utils::{Point2, geometry::interior_points};
+///
+/// let square = [Point2::new(0, 0), Point2::new(3, 0), Point2::new(3, 3), Point2::new(0, 3)];
+/// assert_eq!(interior_points(&square), 4);
+/// ```
+pub fn interior_points(vertices: &[Point2]) -> usize {
+    let doubled = double_area(vertices).unsigned_abs();
+    (doubled + 2 - boundary_points(vertices)) / 2
+}
+
+/// Whether the closed segments `a1`-`a2` and `b1`-`b2` have a point in common,
+/// including touching ends and collinear overlaps.
+pub fn segments_intersect<T: Scalar>(a1: &Point<T, 2>, a2: &Point<T, 2>, b1: &Point<T, 2>, b2: &Point<T, 2>) -> bool {
+    let o1 = orientation(a1, a2, b1);
+    let o2 = orientation(a1, a2, b2);
+    let o3 = orientation(b1, b2, a1);
+    let o4 = orientation(b1, b2, a2);
+
+    if o1 != o2 && o3 != o4 && [o1, o2, o3, o4].iter().all(|&o| o != Ordering::Equal) {
+        return true
+    }
+
+    // within the bounding box of a segment, if collinear with it
+    let on_segment = |p: &Point<T, 2>, q: &Point<T, 2>, r: &Point<T, 2>| {
+        let between = |a: T, b: T, c: T| (a <= c && c <= b) || (b <= c && c <= a);
+        between(p.x(), q.x(), r.x()) && between(p.y(), q.y(), r.y())
+    };
+    (o1 == Ordering::Equal && on_segment(a1, a2, b1))
+        || (o2 == Ordering::Equal && on_segment(a1, a2, b2))
+        || (o3 == Ordering::Equal && on_segment(b1, b2, a1))
+        || (o4 == Ordering::Equal && on_segment(b1, b2, a2))
+}
+
+/// Coordinates rotated by 45 degrees (and scaled), `(x + y, x - y)`. Here,
+/// Manhattan balls are axis-parallel squares and the Manhattan distance is the
+/// Chebyshev distance.
+pub fn rotate(p: &Point2) -> Point2 {
+    Point2::new(p.x() + p.y(), p.x() - p.y())
+}
+
+/// Inverse of `rotate`, `None` for points of different parity, which lie
+/// between lattice points.
+///
+/// ```
+/// use aoc2021::utils::{Point2, geometry::{rotate, unrotate}};
+///
+/// let p = Point2::new(3, -5);
+/// assert_eq!(unrotate(&rotate(&p)), Some(p));
+/// assert_eq!(unrotate(&Point2::new(1, 0)), None);
+/// ```
+pub fn unrotate(p: &Point2) -> Option<Point2> {
+    let (u, v) = (p.x(), p.y());
+    ((u - v) % 2 == 0).then(|| Point2::new((u + v) / 2, (u - v) / 2))
+}
+
+/// The lattice points at exactly the Manhattan distance `radius` of `center`,
+/// counterclockwise starting east.
+pub fn manhattan_ball_boundary(center: Point2, radius: isize) -> impl Iterator<Item=Point2> {
+    let steps = if radius == 0 {1} else {4 * radius};
+    (0..steps).map(move |i| {
+        let (quadrant, j) = (i / radius.max(1), i % radius.max(1));
+        let offset = match quadrant {
+            0 => Point2::new(radius - j, j),
+            1 => Point2::new(-j, radius - j),
+            2 => Point2::new(j - radius, -j),
+            _ => Point2::new(j, j - radius),
+        };
+        center + offset
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polygons() {
+        // an L shape, clockwise
+        let l = [(0, 0), (0, 4), (2, 4), (2, 2), (4, 2), (4, 0)].map(|(x, y)| Point2::new(x, y));
+        assert_eq!(double_area(&l), -24);
+        assert_eq!(boundary_points(&l), 16);
+        assert_eq!(interior_points(&l), 5);
+
+        let triangle = [Point2::new(0, 0), Point2::new(6, 0), Point2::new(0, 4)];
+        assert_eq!(boundary_points(&triangle), 6 + 2 + 4);
+        assert_eq!(interior_points(&triangle), 7);
+    }
+
+    #[test]
+    fn segments() {
+        let p = |x, y| Point2::new(x, y);
+        assert!(segments_intersect(&p(0, 0), &p(4, 4), &p(0, 4), &p(4, 0)));
+        assert!(!segments_intersect(&p(0, 0), &p(1, 1), &p(0, 4), &p(4, 0)));
+        // touching and overlapping
+        assert!(segments_intersect(&p(0, 0), &p(2, 2), &p(2, 2), &p(3, 0)));
+        assert!(segments_intersect(&p(0, 0), &p(4, 0), &p(3, 0), &p(6, 0)));
+        assert!(!segments_intersect(&p(0, 0), &p(2, 0), &p(3, 0), &p(6, 0)));
+        assert_eq!(orientation(&p(0, 0), &p(1, 0), &p(0, 1)), Ordering::Greater);
+    }
+
+    #[test]
+    fn manhattan() {
+        let center = Point2::new(2, -1);
+        let ring: Vec<Point2> = manhattan_ball_boundary(center, 3).collect();
+        assert_eq!(ring.len(), 12);
+        assert!(ring.iter().all(|p| p.manhattan(&center) == 3));
+        assert_eq!(ring.iter().collect::<std::collections::HashSet<_>>().len(), 12);
+        assert_eq!(manhattan_ball_boundary(center, 0).collect::<Vec<_>>(), vec![center]);
+
+        // Manhattan distance becomes Chebyshev distance
+        let a = Point2::new(-3, 7);
+        assert_eq!(rotate(&a).chebyshev(&rotate(&center)), a.manhattan(&center));
+    }
+}
@@ -1,60 +1,207 @@
-use std::ops::Index;
+use std::{ops::Index, hash::Hash, borrow::Borrow};
+
+use rustc_hash::FxHashMap;
 
 use super::shortest_path::Neighborful;
 
-pub trait Indexable {
-    fn idx(&self) -> usize;
+/// A graph stored as adjacency lists, whose nodes carry hashable labels.
+///
+/// Labels are interned, i.e., every new label is assigned a dense id on
+/// insertion, which is used to refer to the node afterwards. Ids of removed
+/// nodes are not reused.
+#[derive(Debug, Clone)]
+pub struct AdjList<L> {
+    directed: bool,
+    labels: Vec<L>,
+    ids: FxHashMap<L, usize>,
+    /// outgoing edges as (target, weight)
+    adj: Vec<Vec<(usize, usize)>>,
+    removed: Vec<bool>,
 }
 
-pub struct AdjList<T: Indexable> {
-    nodes: Vec<T>,
-    adj: Vec<Vec<usize>>,
-}
+impl<L: Hash + Eq + Clone> AdjList<L> {
+    /// A graph, whose edges can be traversed in both directions.
+    pub fn undirected() -> AdjList<L> {
+        AdjList {
+            directed: false,
+            labels: Vec::new(),
+            ids: FxHashMap::default(),
+            adj: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
 
-impl<T: Indexable> AdjList<T> {
-    pub fn new(nodes: Vec<T>) -> AdjList<T> {
-        let adj = vec![Vec::new(); nodes.len()];
+    /// A graph, whose edges can only be traversed from source to target.
+    pub fn directed() -> AdjList<L> {
         AdjList {
-            nodes,
-            adj,
+            directed: true,
+            ..AdjList::undirected()
         }
     }
 
-    pub fn add_edge(&mut self, s: T, t: T) {
-        let s = s.idx();
-        let t = t.idx();
+    /// Returns the id of the node with this label, which is inserted if it is not yet known.
+    pub fn add_node(&mut self, label: L) -> usize {
+        if let Some(&id) = self.ids.get(&label) {
+            self.removed[id] = false;
+            return id
+        }
+
+        let id = self.labels.len();
+        self.ids.insert(label.clone(), id);
+        self.labels.push(label);
+        self.adj.push(Vec::new());
+        self.removed.push(false);
+        id
+    }
 
-        self.adj[s].push(t);
-        self.adj[t].push(s);
+    /// Inserts an edge of weight 1 and returns the ids of its endpoints.
+    pub fn add_edge(&mut self, s: L, t: L) -> (usize, usize) {
+        self.add_weighted_edge(s, t, 1)
     }
 
-    pub fn size(&self) -> usize {
-        self.nodes.len()
+    pub fn add_weighted_edge(&mut self, s: L, t: L, weight: usize) -> (usize, usize) {
+        let s = self.add_node(s);
+        let t = self.add_node(t);
+
+        self.adj[s].push((t, weight));
+        if !self.directed && s != t {
+            self.adj[t].push((s, weight));
+        }
+
+        (s, t)
+    }
+
+    /// Removes all edges from `s` to `t` and returns whether there were any.
+    pub fn remove_edge(&mut self, s: usize, t: usize) -> bool {
+        let before = self.adj[s].len();
+        self.adj[s].retain(|&(n, _)| n != t);
+        if !self.directed {
+            self.adj[t].retain(|&(n, _)| n != s);
+        }
+        self.adj[s].len() != before
+    }
+
+    /// Removes a node together with all edges from and to it.
+    pub fn remove_node(&mut self, id: usize) {
+        self.removed[id] = true;
+        self.adj[id].clear();
+        for edges in self.adj.iter_mut() {
+            edges.retain(|&(n, _)| n != id);
+        }
+    }
+
+    pub fn id<Q>(&self, label: &Q) -> Option<usize>
+        where L: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        self.ids.get(label).cloned().filter(|&id| !self.removed[id])
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        id < self.labels.len() && !self.removed[id]
+    }
+
+    /// The weight of the lightest edge from `s` to `t`, if there is one.
+    pub fn weight(&self, s: usize, t: usize) -> Option<usize> {
+        self.edges(s)
+            .filter(|&(n, _)| n == t)
+            .map(|(_, w)| w)
+            .min()
     }
 }
 
-impl<T: Indexable> Index<usize> for AdjList<T> {
-    type Output = T;
+impl<L> AdjList<L> {
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Outgoing edges of a node as (target, weight).
+    pub fn edges(&self, id: usize) -> impl Iterator<Item=(usize, usize)> + '_ {
+        self.adj[id].iter().cloned()
+    }
+
+    /// Ids of all nodes, which were not removed.
+    pub fn nodes(&self) -> impl Iterator<Item=usize> + '_ {
+        (0..self.labels.len()).filter(|&id| !self.removed[id])
+    }
 
-    fn index(&self, idx: usize) -> &Self::Output {
-        &self.nodes[idx]
+    /// Number of nodes, which were not removed.
+    pub fn len(&self) -> usize {
+        self.removed.iter().filter(|&&r| !r).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Upper bound of the node ids, e.g., to allocate lookup tables indexed by id.
+    pub fn size(&self) -> usize {
+        self.labels.len()
     }
 }
 
-impl<T: Indexable> Index<T> for AdjList<T> {
-    type Output = T;
+impl<L> Index<usize> for AdjList<L> {
+    type Output = L;
 
-    fn index(&self, node: T) -> &Self::Output {
-        &self.nodes[node.idx()]
+    fn index(&self, id: usize) -> &Self::Output {
+        &self.labels[id]
     }
 }
 
-impl<T: Indexable> Neighborful<usize> for AdjList<T> {
+impl<L> Neighborful<usize> for AdjList<L> {
     fn neighbors(&self, coordinate: usize) -> impl Iterator<Item=usize> + '_ {
-        self.adj[coordinate].iter().cloned()
+        self.adj[coordinate].iter().map(|&(n, _)| n)
     }
 
+    /// Nodes of a general graph have no positions, so the only
+    /// lower bound on their distance is zero.
     fn distance(_c1: usize, _c2: usize) -> usize {
-        unimplemented!()
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undirected() {
+        let mut g = AdjList::undirected();
+        let (a, b) = g.add_edge("a", "b");
+        let (_, c) = g.add_weighted_edge("b", "c", 5);
+        assert_eq!(g.add_node("a"), a);
+        assert_eq!(g.id("c"), Some(c));
+        assert_eq!(g[b], "b");
+        assert_eq!(g.len(), 3);
+
+        assert_eq!(g.neighbors(b).collect::<Vec<_>>(), vec![a, c]);
+        assert_eq!(g.weight(c, b), Some(5));
+        assert_eq!(g.weight(a, c), None);
+
+        assert!(g.remove_edge(c, b));
+        assert!(!g.remove_edge(b, c));
+        assert_eq!(g.neighbors(b).collect::<Vec<_>>(), vec![a]);
+
+        g.remove_node(a);
+        assert_eq!(g.neighbors(b).count(), 0);
+        assert_eq!(g.id("a"), None);
+        assert_eq!(g.nodes().collect::<Vec<_>>(), vec![b, c]);
+        assert_eq!(g.size(), 3);
+    }
+
+    #[test]
+    fn directed() {
+        let mut g: AdjList<String> = AdjList::directed();
+        let (a, b) = g.add_edge("AAA".to_string(), "BBB".to_string());
+        g.add_edge("BBB".to_string(), "AAA".to_string());
+        g.add_edge("CCC".to_string(), "AAA".to_string());
+
+        assert_eq!(g.neighbors(a).collect::<Vec<_>>(), vec![b]);
+        assert!(g.remove_edge(a, b));
+        assert_eq!(g.neighbors(a).count(), 0);
+        assert_eq!(g.neighbors(b).collect::<Vec<_>>(), vec![a]);
+
+        g.remove_node(a);
+        assert!(!g.contains(a));
+        assert!(g.nodes().all(|n| g.neighbors(n).count() == 0));
     }
 }
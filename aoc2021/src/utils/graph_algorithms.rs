@@ -0,0 +1,470 @@
+use std::collections::VecDeque;
+
+use rustc_hash::FxHashSet;
+
+use super::{AdjList, UnionFind, shortest_path::Neighborful};
+
+/// Result of a maximum flow calculation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flow {
+    pub value: usize,
+    /// nodes on the source side of a minimum cut
+    pub source_side: Vec<usize>,
+    /// edges of a minimum cut, their capacities sum up to `value`
+    pub cut: Vec<(usize, usize)>,
+}
+
+impl<L> AdjList<L> {
+    fn adjacency(&self) -> Vec<Vec<usize>> {
+        (0..self.size()).map(|v| self.neighbors(v).collect()).collect()
+    }
+
+    /// Orders the nodes of a directed graph such that all edges point forward.
+    ///
+    /// If this is not possible, the error contains the nodes of a cycle in order.
+    pub fn topological_sort(&self) -> Result<Vec<usize>, Vec<usize>> {
+        let mut in_degree = vec![0; self.size()];
+        for v in self.nodes() {
+            for w in self.neighbors(v) {
+                in_degree[w] += 1;
+            }
+        }
+
+        // Kahn's algorithm
+        let mut queue: VecDeque<usize> = self.nodes().filter(|&v| in_degree[v] == 0).collect();
+        let mut order = Vec::new();
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            for w in self.neighbors(v) {
+                in_degree[w] -= 1;
+                if in_degree[w] == 0 {
+                    queue.push_back(w);
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            return Ok(order)
+        }
+
+        // every remaining node has a remaining predecessor, so walking
+        // backwards along them has to run into a cycle eventually
+        let mut predecessor = vec![None; self.size()];
+        for v in self.nodes().filter(|&v| in_degree[v] > 0) {
+            for w in self.neighbors(v).filter(|&w| in_degree[w] > 0) {
+                predecessor[w] = Some(v);
+            }
+        }
+
+        let mut position = vec![None; self.size()];
+        let mut walk = Vec::new();
+        let mut current = self.nodes().find(|&v| in_degree[v] > 0).unwrap();
+        while position[current].is_none() {
+            position[current] = Some(walk.len());
+            walk.push(current);
+            current = predecessor[current].unwrap();
+        }
+
+        let mut cycle = walk.split_off(position[current].unwrap());
+        cycle.reverse();
+        Err(cycle)
+    }
+
+    /// Strongly connected components of a directed graph (Tarjan's algorithm).
+    ///
+    /// The components are returned in reverse topological order, i.e.,
+    /// no edge leads from a component to a later one.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let adj = self.adjacency();
+        let mut index = vec![usize::MAX; self.size()];
+        let mut low = vec![0; self.size()];
+        let mut on_stack = vec![false; self.size()];
+        let mut stack = Vec::new();
+        let mut counter = 0;
+        let mut components = Vec::new();
+
+        for root in self.nodes() {
+            if index[root] != usize::MAX {
+                continue;
+            }
+
+            index[root] = counter;
+            low[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            // explicit call stack of (node, position in its adjacency list)
+            let mut calls = vec![(root, 0)];
+            while let Some((v, pos)) = calls.last_mut() {
+                let v = *v;
+                if let Some(&w) = adj[v].get(*pos) {
+                    *pos += 1;
+                    if index[w] == usize::MAX {
+                        index[w] = counter;
+                        low[w] = counter;
+                        counter += 1;
+                        stack.push(w);
+                        on_stack[w] = true;
+                        calls.push((w, 0));
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(index[w]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(u, _)) = calls.last() {
+                    low[u] = low[u].min(low[v]);
+                }
+                if low[v] == index[v] {
+                    let mut component = Vec::new();
+                    while let Some(w) = stack.pop() {
+                        on_stack[w] = false;
+                        component.push(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Discovery times and low links of a depth first search of an undirected graph.
+    ///
+    /// Returns the bridges and for every node whether it is an articulation point.
+    fn lowlinks(&self) -> (Vec<(usize, usize)>, Vec<bool>) {
+        let adj = self.adjacency();
+        let mut discovered = vec![usize::MAX; self.size()];
+        let mut low = vec![0; self.size()];
+        let mut counter = 0;
+        let mut bridges = Vec::new();
+        let mut articulation = vec![false; self.size()];
+
+        for root in self.nodes() {
+            if discovered[root] != usize::MAX {
+                continue;
+            }
+
+            discovered[root] = counter;
+            low[root] = counter;
+            counter += 1;
+            let mut root_children = 0;
+
+            // explicit call stack of (node, parent, position in its adjacency list,
+            // whether the edge to the parent was already skipped)
+            let mut calls = vec![(root, usize::MAX, 0, false)];
+            while let Some((v, parent, pos, skipped)) = calls.last_mut() {
+                let v = *v;
+                if let Some(&w) = adj[v].get(*pos) {
+                    *pos += 1;
+                    // only skip the edge we came along, parallel edges are cycles
+                    if w == *parent && !*skipped {
+                        *skipped = true;
+                        continue;
+                    }
+                    if discovered[w] == usize::MAX {
+                        discovered[w] = counter;
+                        low[w] = counter;
+                        counter += 1;
+                        if v == root {
+                            root_children += 1;
+                        }
+                        calls.push((w, v, 0, false));
+                    } else {
+                        low[v] = low[v].min(discovered[w]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(u, _, _, _)) = calls.last() {
+                    low[u] = low[u].min(low[v]);
+                    if low[v] > discovered[u] {
+                        bridges.push((u, v));
+                    }
+                    if u != root && low[v] >= discovered[u] {
+                        articulation[u] = true;
+                    }
+                }
+            }
+
+            if root_children > 1 {
+                articulation[root] = true;
+            }
+        }
+
+        (bridges, articulation)
+    }
+
+    /// Edges of an undirected graph, whose removal disconnects their endpoints.
+    pub fn bridges(&self) -> Vec<(usize, usize)> {
+        self.lowlinks().0
+    }
+
+    /// Nodes of an undirected graph, whose removal increases the number of components.
+    pub fn articulation_points(&self) -> Vec<usize> {
+        let (_, articulation) = self.lowlinks();
+        self.nodes().filter(|&v| articulation[v]).collect()
+    }
+
+    /// Minimum spanning forest as (source, target, weight) (Kruskal's algorithm).
+    ///
+    /// The direction of edges is ignored.
+    pub fn minimum_spanning_tree(&self) -> Vec<(usize, usize, usize)> {
+        let mut edges: Vec<(usize, usize, usize)> = self.nodes()
+            .flat_map(|s| self.edges(s).map(move |(t, w)| (s, t, w)))
+            // undirected edges are stored in both directions
+            .filter(|&(s, t, _)| s != t && (self.is_directed() || s < t))
+            .collect();
+        edges.sort_by_key(|&(_, _, w)| w);
+
+        let mut clusters = UnionFind::new(self.size());
+        let mut tree = Vec::new();
+        for (s, t, w) in edges {
+            if clusters.find(s) != clusters.find(t) {
+                clusters.union(s, t);
+                tree.push((s, t, w));
+            }
+        }
+
+        tree
+    }
+
+    /// Maximum flow from `source` to `sink`, where the weights are the
+    /// capacities of the edges (Edmonds-Karp algorithm).
+    ///
+    /// Edges of undirected graphs have their capacity in both directions.
+    pub fn max_flow(&self, source: usize, sink: usize) -> Flow {
+        assert_ne!(source, sink);
+
+        // residual network, edge `e` and `e ^ 1` are the reverse of each other
+        let mut target = Vec::new();
+        let mut capacity = Vec::new();
+        let mut outgoing = vec![Vec::new(); self.size()];
+        for s in self.nodes() {
+            for (t, w) in self.edges(s) {
+                outgoing[s].push(target.len());
+                target.push(t);
+                capacity.push(w);
+                outgoing[t].push(target.len());
+                target.push(s);
+                capacity.push(0);
+            }
+        }
+
+        let mut value = 0;
+        loop {
+            // shortest augmenting path by breadth first search
+            let mut via = vec![None; self.size()];
+            let mut reached = vec![false; self.size()];
+            reached[source] = true;
+            let mut queue = VecDeque::from([source]);
+            while let Some(v) = queue.pop_front() {
+                for &e in &outgoing[v] {
+                    let w = target[e];
+                    if capacity[e] > 0 && !reached[w] {
+                        reached[w] = true;
+                        via[w] = Some(e);
+                        queue.push_back(w);
+                    }
+                }
+            }
+
+            if !reached[sink] {
+                let source_side: Vec<usize> = self.nodes().filter(|&v| reached[v]).collect();
+                let cut = source_side.iter()
+                    .flat_map(|&s| self.neighbors(s).map(move |t| (s, t)))
+                    .filter(|&(_, t)| !reached[t])
+                    .collect();
+                return Flow {
+                    value,
+                    source_side,
+                    cut,
+                }
+            }
+
+            let mut bottleneck = usize::MAX;
+            let mut v = sink;
+            while let Some(e) = via[v] {
+                bottleneck = bottleneck.min(capacity[e]);
+                v = target[e ^ 1];
+            }
+            let mut v = sink;
+            while let Some(e) = via[v] {
+                capacity[e] -= bottleneck;
+                capacity[e ^ 1] += bottleneck;
+                v = target[e ^ 1];
+            }
+            value += bottleneck;
+        }
+    }
+
+    /// All maximal cliques of an undirected graph (Bron-Kerbosch with pivoting).
+    ///
+    /// Every clique is sorted and the cliques are sorted lexicographically.
+    pub fn maximal_cliques(&self) -> Vec<Vec<usize>> {
+        let adj: Vec<FxHashSet<usize>> = (0..self.size())
+            .map(|v| self.neighbors(v).filter(|&w| w != v).collect())
+            .collect();
+
+        fn bron_kerbosch(
+            adj: &[FxHashSet<usize>],
+            clique: &mut Vec<usize>,
+            mut candidates: FxHashSet<usize>,
+            mut excluded: FxHashSet<usize>,
+            out: &mut Vec<Vec<usize>>
+        ) {
+            let Some(&pivot) = candidates.union(&excluded)
+                .max_by_key(|&&u| candidates.intersection(&adj[u]).count())
+            else {
+                let mut found = clique.clone();
+                found.sort_unstable();
+                out.push(found);
+                return
+            };
+
+            let branches: Vec<usize> = candidates.difference(&adj[pivot]).cloned().collect();
+            for v in branches {
+                clique.push(v);
+                bron_kerbosch(
+                    adj,
+                    clique,
+                    candidates.intersection(&adj[v]).cloned().collect(),
+                    excluded.intersection(&adj[v]).cloned().collect(),
+                    out
+                );
+                clique.pop();
+                candidates.remove(&v);
+                excluded.insert(v);
+            }
+        }
+
+        let mut out = Vec::new();
+        bron_kerbosch(&adj, &mut Vec::new(), self.nodes().collect(), FxHashSet::default(), &mut out);
+        out.sort_unstable();
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directed(edges: &[(usize, usize)]) -> AdjList<usize> {
+        let mut g = AdjList::directed();
+        for &(s, t) in edges {
+            g.add_edge(s, t);
+        }
+        g
+    }
+
+    fn undirected(edges: &[(usize, usize)]) -> AdjList<usize> {
+        let mut g = AdjList::undirected();
+        for &(s, t) in edges {
+            g.add_edge(s, t);
+        }
+        g
+    }
+
+    #[test]
+    fn topological() {
+        let g = directed(&[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)]);
+        let order = g.topological_sort().unwrap();
+        let position = |v: usize| order.iter().position(|&w| w == g.id(&v).unwrap()).unwrap();
+        assert_eq!(order.len(), 5);
+        assert!(position(0) < position(1) && position(1) < position(3) && position(2) < position(3));
+
+        let g = directed(&[(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)]);
+        let mut cycle: Vec<usize> = g.topological_sort().unwrap_err().iter().map(|&v| g[v]).collect();
+        // the cycle may start anywhere, but has to be in the direction of the edges
+        let start = cycle.iter().position(|&v| v == 1).unwrap();
+        cycle.rotate_left(start);
+        assert_eq!(cycle, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn scc() {
+        let g = directed(&[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (5, 4)]);
+        let components: Vec<Vec<usize>> = g.strongly_connected_components().iter()
+            .map(|c| {
+                let mut c: Vec<usize> = c.iter().map(|&v| g[v]).collect();
+                c.sort();
+                c
+            })
+            .collect();
+        assert_eq!(components, vec![vec![3, 4], vec![0, 1, 2], vec![5]]);
+    }
+
+    #[test]
+    fn bridges_and_articulation_points() {
+        // two triangles connected by the bridge 2-3, and a pendant 5-6
+        let g = undirected(&[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3), (5, 6)]);
+        let mut bridges: Vec<(usize, usize)> = g.bridges().iter()
+            .map(|&(s, t)| (g[s].min(g[t]), g[s].max(g[t])))
+            .collect();
+        bridges.sort();
+        assert_eq!(bridges, vec![(2, 3), (5, 6)]);
+
+        let mut points: Vec<usize> = g.articulation_points().iter().map(|&v| g[v]).collect();
+        points.sort();
+        assert_eq!(points, vec![2, 3, 5]);
+
+        // parallel edges are no bridges
+        let g = undirected(&[(0, 1), (0, 1), (1, 2)]);
+        assert_eq!(g.bridges(), vec![(1, 2)]);
+        assert_eq!(g.articulation_points(), vec![1]);
+    }
+
+    #[test]
+    fn spanning_tree() {
+        let mut g = AdjList::undirected();
+        g.add_weighted_edge('a', 'b', 4);
+        g.add_weighted_edge('a', 'c', 1);
+        g.add_weighted_edge('b', 'c', 2);
+        g.add_weighted_edge('b', 'd', 5);
+        g.add_weighted_edge('c', 'd', 8);
+        g.add_weighted_edge('e', 'f', 3);
+
+        let tree = g.minimum_spanning_tree();
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.iter().map(|&(_, _, w)| w).sum::<usize>(), 11);
+    }
+
+    #[test]
+    fn flow() {
+        let mut g = AdjList::directed();
+        g.add_weighted_edge("s", "a", 10);
+        g.add_weighted_edge("s", "b", 5);
+        g.add_weighted_edge("a", "b", 15);
+        g.add_weighted_edge("a", "t", 4);
+        g.add_weighted_edge("b", "t", 10);
+        let (s, t) = (g.id("s").unwrap(), g.id("t").unwrap());
+
+        let flow = g.max_flow(s, t);
+        assert_eq!(flow.value, 14);
+        let mut cut: Vec<(&str, &str)> = flow.cut.iter().map(|&(u, v)| (g[u], g[v])).collect();
+        cut.sort();
+        assert_eq!(cut, vec![("a", "t"), ("b", "t")]);
+        assert!(flow.source_side.contains(&s) && !flow.source_side.contains(&t));
+
+        // two undirected unit paths between 0 and 3
+        let g = undirected(&[(0, 1), (1, 3), (0, 2), (2, 3), (1, 2)]);
+        let flow = g.max_flow(g.id(&0).unwrap(), g.id(&3).unwrap());
+        assert_eq!(flow.value, 2);
+        assert_eq!(flow.cut.len(), 2);
+    }
+
+    #[test]
+    fn cliques() {
+        let g = undirected(&[(0, 1), (0, 2), (1, 2), (1, 3), (2, 3), (3, 4), (5, 5)]);
+        let cliques: Vec<Vec<usize>> = g.maximal_cliques().iter()
+            .map(|c| c.iter().map(|&v| g[v]).collect())
+            .collect();
+        assert_eq!(cliques, vec![vec![0, 1, 2], vec![1, 2, 3], vec![3, 4], vec![5]]);
+    }
+}
@@ -0,0 +1,252 @@
+use std::ops::Range;
+
+use super::Scalar;
+
+/// Set of integers as sorted, disjoint and non-adjacent half-open ranges.
+///
+/// ```
+/// use aoc2021::utils::intervals::IntervalSet;
+///
+/// let a: IntervalSet<i64> = [0..5, 10..15].into_iter().collect();
+/// let b: IntervalSet<i64> = [3..12].into_iter().collect();
+/// assert_eq!(a.union(&b).ranges(), &[0..15]);
+/// assert_eq!(a.intersection(&b).ranges(), &[3..5, 10..12]);
+/// assert_eq!(a.difference(&b).ranges(), &[0..3, 12..15]);
+/// assert_eq!(a.gaps(-2..20).collect::<Vec<_>>(), vec![-2..0, 5..10, 15..20]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { ranges: Vec::new() }
+    }
+}
+
+impl<T: Scalar + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut input: Vec<Range<T>> = iter.into_iter().filter(|r| !r.is_empty()).collect();
+        input.sort_unstable_by_key(|r| r.start);
+
+        let mut ranges: Vec<Range<T>> = Vec::with_capacity(input.len());
+        for r in input {
+            match ranges.last_mut() {
+                Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+                _ => ranges.push(r),
+            }
+        }
+        IntervalSet { ranges }
+    }
+}
+
+impl<T: Scalar + Ord> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet::default()
+    }
+
+    /// The maximal ranges of the set in ascending order.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=Range<T>> + '_ {
+        self.ranges.iter().cloned()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        *self = self.iter().chain(std::iter::once(range)).collect();
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    /// Whether the set contains every element of `range`.
+    pub fn covers(&self, range: &Range<T>) -> bool {
+        if range.is_empty() {
+            return true
+        }
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+        self.ranges.get(i).is_some_and(|r| r.start <= range.start && range.end <= r.end)
+    }
+
+    /// Whether the set contains any element of `range`.
+    pub fn overlaps(&self, range: &Range<T>) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+        self.ranges.get(i).is_some_and(|r| r.start < range.end && !range.is_empty())
+    }
+
+    /// Number of elements in the set.
+    pub fn total_length(&self) -> T {
+        self.ranges.iter().map(|r| r.end - r.start).sum()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        self.iter().chain(other.iter()).collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            // the range ending first cannot overlap anything further
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// Elements of `self`, which are not in `other`.
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for a in &self.ranges {
+            let mut start = a.start;
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < a.end {
+                let b = &other.ranges[k];
+                if start < b.start {
+                    ranges.push(start..b.start);
+                }
+                start = start.max(b.end);
+                k += 1;
+            }
+            if start < a.end {
+                ranges.push(start..a.end);
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// The maximal ranges within `bounds`, which are not in the set.
+    pub fn gaps(&self, bounds: Range<T>) -> impl Iterator<Item=Range<T>> {
+        IntervalSet::from_iter([bounds]).difference(self).ranges.into_iter()
+    }
+}
+
+/// Piecewise shift of integers: every source range is moved to start at its
+/// destination, values outside all source ranges are mapped to themselves.
+///
+/// If source ranges overlap, the one inserted first takes precedence.
+///
+/// ```
+/// use aoc2021::utils::intervals::{IntervalSet, RangeMap};
+///
+/// let mut map = RangeMap::new();
+/// map.insert(98..100, 50u64);
+/// map.insert(50..98, 52);
+/// assert_eq!(map.get(99), 51);
+/// assert_eq!(map.get(10), 10);
+///
+/// let seeds: IntervalSet<u64> = [79..93, 96..100].into_iter().collect();
+/// assert_eq!(map.map_set(&seeds).ranges(), &[50..52, 81..95, 98..100]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeMap<T> {
+    /// source ranges and the destination of their start
+    pieces: Vec<(Range<T>, T)>,
+}
+
+impl<T> Default for RangeMap<T> {
+    fn default() -> Self {
+        RangeMap { pieces: Vec::new() }
+    }
+}
+
+impl<T: Scalar + Ord> RangeMap<T> {
+    pub fn new() -> RangeMap<T> {
+        RangeMap::default()
+    }
+
+    pub fn insert(&mut self, source: Range<T>, destination: T) {
+        self.pieces.push((source, destination));
+    }
+
+    pub fn get(&self, value: T) -> T {
+        self.pieces.iter()
+            .find(|(source, _)| source.contains(&value))
+            .map_or(value, |(source, destination)| *destination + (value - source.start))
+    }
+
+    /// The image of a whole set, calculated range by range.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut remaining = set.clone();
+        let mut images = Vec::new();
+        for (source, destination) in &self.pieces {
+            let source = IntervalSet::from_iter([source.clone()]);
+            for r in remaining.intersection(&source).iter() {
+                let start = *destination + (r.start - source.ranges[0].start);
+                images.push(start..start + (r.end - r.start));
+            }
+            remaining = remaining.difference(&source);
+        }
+        images.into_iter().chain(remaining.iter()).collect()
+    }
+}
+
+#[cfg(test)]
+// single ranges are sets of one range here, not vectors of their elements
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn algebra() {
+        let a: IntervalSet<usize> = [5..8, 0..3, 2..4, 8..9, 7..7].into_iter().collect();
+        assert_eq!(a.ranges(), &[0..4, 5..9]);
+        assert_eq!(a.total_length(), 8);
+        assert!(a.contains(0) && a.contains(8) && !a.contains(4) && !a.contains(9));
+        assert!(a.covers(&(5..9)) && !a.covers(&(3..6)));
+        assert!(a.overlaps(&(3..6)) && !a.overlaps(&(4..5)));
+
+        let b: IntervalSet<usize> = [1..2, 3..6, 8..20].into_iter().collect();
+        assert_eq!(a.intersection(&b).ranges(), &[1..2, 3..4, 5..6, 8..9]);
+        assert_eq!(a.difference(&b).ranges(), &[0..1, 2..3, 6..8]);
+        assert_eq!(b.difference(&a).ranges(), &[4..5, 9..20]);
+        assert_eq!(a.union(&b).ranges(), &[0..20]);
+        assert_eq!(a.difference(&a), IntervalSet::new());
+
+        let mut c = IntervalSet::new();
+        c.insert(10..12);
+        c.insert(0..1);
+        assert_eq!(c.gaps(0..15).collect::<Vec<_>>(), vec![1..10, 12..15]);
+        assert_eq!(c.min(), Some(0));
+    }
+
+    #[test]
+    fn chained_maps() {
+        let mut first = RangeMap::new();
+        first.insert(0..10, 100i64);
+        let mut second = RangeMap::new();
+        second.insert(105..200, 0);
+        // the first piece shadows the overlapping part of this one
+        second.insert(100..120, 50);
+
+        let set: IntervalSet<i64> = [5..15].into_iter().collect();
+        let image = [first, second].iter().fold(set, |s, m| m.map_set(&s));
+        assert_eq!(image.ranges(), &[0..5, 10..15]);
+    }
+}
@@ -0,0 +1,221 @@
+use std::ops::{Index, IndexMut, Add, Sub, Mul, Div};
+
+use super::{AdventError, checked::Checked};
+
+/// Dense matrix over integers, `ModInt` or `Rational`, whose arithmetic
+/// reports overflow as an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    /// row major
+    data: Vec<T>,
+}
+
+impl<T: Checked> Matrix<T> {
+    pub fn zero(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix { rows, cols, data: vec![T::zero(); rows * cols] }
+    }
+
+    pub fn identity(n: usize) -> Matrix<T> {
+        let mut m = Matrix::zero(n, n);
+        for i in 0..n {
+            m[(i, i)] = T::one();
+        }
+        m
+    }
+
+    /// Panics, if the rows differ in length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Matrix<T> {
+        let cols = rows.first().map_or(0, |r| r.len());
+        assert!(rows.iter().all(|r| r.len() == cols), "rows of different length");
+        Matrix { rows: rows.len(), cols, data: rows.into_iter().flatten().collect() }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn try_mul(&self, other: &Matrix<T>) -> Result<Matrix<T>, AdventError> {
+        assert_eq!(self.cols, other.rows, "incompatible shapes");
+        let mut result: Matrix<T> = Matrix::zero(self.rows, other.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = &self[(i, k)];
+                for j in 0..other.cols {
+                    result[(i, j)] = result[(i, j)].try_add(&a.try_mul(&other[(k, j)])?)?;
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// The matrix applied to a column vector.
+    pub fn try_apply(&self, vector: &[T]) -> Result<Vec<T>, AdventError> {
+        assert_eq!(self.cols, vector.len(), "incompatible shapes");
+        (0..self.rows)
+            .map(|i| (0..self.cols).try_fold(T::zero(), |acc, j| acc.try_add(&self[(i, j)].try_mul(&vector[j])?)))
+            .collect()
+    }
+
+    /// `self^exp` by repeated squaring, i.e., with `O(log exp)` multiplications.
+    pub fn try_pow(&self, mut exp: u64) -> Result<Matrix<T>, AdventError> {
+        assert_eq!(self.rows, self.cols, "only square matrices have powers");
+        let mut result = Matrix::identity(self.rows);
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.try_mul(&base)?;
+            }
+            exp >>= 1;
+            // the last square would be unused and might overflow needlessly
+            if exp > 0 {
+                base = base.try_mul(&base)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+/// Shortest linear recurrence `s[n] = sum_i c[i] s[n - 1 - i]` generating the
+/// sequence, by the Berlekamp–Massey algorithm. Needs exact division, so the
+/// elements have to form a field, e.g., `Rational` or `ModInt` with a prime modulus.
+///
+/// The recurrence is only reliable, if the sequence is at least twice as long as it.
+///
+/// ```
+/// use aoc2021::utils::{matrix::berlekamp_massey, rational::Rational};
+///
+/// let fibonacci = [1, 1, 2, 3, 5, 8, 13, 21].map(|x: i64| Rational::from(x));
+/// assert_eq!(berlekamp_massey(&fibonacci), vec![Rational::ONE, Rational::ONE]);
+/// ```
+pub fn berlekamp_massey<T>(sequence: &[T]) -> Vec<T>
+    where T: Checked + Copy + PartialEq + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Div<Output=T>
+{
+    // connection polynomials with c[0] = 1, the current and the one before the last length change
+    let mut current = vec![T::one()];
+    let mut previous = vec![T::one()];
+    let mut length = 0;
+    // steps since the last length change and the discrepancy at that point
+    let mut shift = 1;
+    let mut last_discrepancy = T::one();
+
+    for n in 0..sequence.len() {
+        let discrepancy = (1..=length).fold(sequence[n], |d, i| d + current[i] * sequence[n - i]);
+        if discrepancy == T::zero() {
+            shift += 1;
+            continue
+        }
+
+        let factor = discrepancy / last_discrepancy;
+        let before = current.clone();
+        if current.len() < previous.len() + shift {
+            current.resize(previous.len() + shift, T::zero());
+        }
+        for (i, &p) in previous.iter().enumerate() {
+            current[i + shift] = current[i + shift] - factor * p;
+        }
+
+        if 2 * length <= n {
+            length = n + 1 - length;
+            previous = before;
+            last_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+    }
+
+    current.resize(length + 1, T::zero());
+    // s[n] + sum_i current[i] s[n - i] = 0
+    current[1..].iter().map(|&c| T::zero() - c).collect()
+}
+
+/// The `n`-th element (starting at 0) of the sequence given by a recurrence as
+/// returned by `berlekamp_massey` and its first elements, in `O(k^3 log n)`.
+pub fn nth_term<T: Checked>(coefficients: &[T], initial: &[T], n: u64) -> Result<T, AdventError> {
+    let k = coefficients.len();
+    assert!(initial.len() >= k, "not enough initial elements");
+    if n < initial.len() as u64 {
+        return Ok(initial[n as usize].clone())
+    }
+    if k == 0 {
+        return Ok(T::zero())
+    }
+
+    // companion matrix, which shifts the window of the last k elements by one
+    let mut companion = Matrix::zero(k, k);
+    for (j, c) in coefficients.iter().enumerate() {
+        companion[(0, j)] = c.clone();
+    }
+    for i in 1..k {
+        companion[(i, i - 1)] = T::one();
+    }
+
+    // window ending at the last given element, most recent first
+    let last = initial.len() - 1;
+    let window: Vec<T> = (0..k).map(|i| initial[last - i].clone()).collect();
+    let advanced = companion.try_pow(n - last as u64)?.try_apply(&window)?;
+    Ok(advanced[0].clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{number_theory::ModInt, rational::Rational};
+
+    use super::*;
+
+    #[test]
+    fn powers() {
+        let fibonacci = Matrix::from_rows(vec![vec![1u64, 1], vec![1, 0]]);
+        assert_eq!(fibonacci.try_pow(90).unwrap()[(0, 1)], 2880067194370816120);
+        assert!(matches!(fibonacci.try_pow(100), Err(AdventError::Overflow)));
+        assert_eq!(fibonacci.try_pow(0).unwrap(), Matrix::identity(2));
+        assert_eq!(fibonacci.try_apply(&[3, 2]).unwrap(), vec![5, 3]);
+
+        type P = ModInt<1_000_000_007>;
+        let modular = Matrix::from_rows(vec![vec![P::new(1), P::new(1)], vec![P::new(1), P::new(0)]]);
+        // F(10^18) mod 10^9 + 7
+        assert_eq!(modular.try_pow(1_000_000_000_000_000_000).unwrap()[(0, 1)], P::new(209783453));
+    }
+
+    #[test]
+    fn recurrences() {
+        // s[n] = 2 s[n - 1] - s[n - 3] + 1, which is of order 4 as a homogeneous recurrence
+        let mut s: Vec<i64> = vec![1, 0, 3];
+        for n in 3..20 {
+            s.push(2 * s[n - 1] - s[n - 3] + 1);
+        }
+        let rational: Vec<Rational> = s.iter().map(|&x| Rational::from(x)).collect();
+        let coefficients = berlekamp_massey(&rational);
+        assert_eq!(coefficients.len(), 4);
+        for n in 10..20 {
+            assert_eq!(nth_term(&coefficients, &rational[..8], n as u64).unwrap(), rational[n]);
+        }
+
+        type P = ModInt<998_244_353>;
+        let modular: Vec<P> = s.iter().map(|&x| P::new(x)).collect();
+        let coefficients = berlekamp_massey(&modular);
+        assert_eq!(coefficients, [3, -2, -1, 1].map(P::new));
+
+        assert!(berlekamp_massey(&[Rational::ZERO; 5]).is_empty());
+    }
+}
@@ -4,6 +4,24 @@ mod test;
 mod union_find;
 mod two_dimensional;
 mod graph;
+pub mod graph_algorithms;
+pub mod paths;
+pub mod search;
+pub mod cycles;
+pub mod number_theory;
+pub mod intervals;
+pub mod boxes;
+pub mod rational;
+pub mod polynomial;
+pub mod checked;
+pub mod matrix;
+pub mod geometry;
+pub mod visualize;
+mod point;
+pub mod rotation;
+pub mod regions;
+pub mod automaton;
+pub mod bitgrid;
 pub mod letters;
 pub mod binary;
 pub mod files;
@@ -11,6 +29,9 @@ pub mod shortest_path;
 
 pub use string::*;
 pub use advent_error::AdventError;
-pub use union_find::UnionFind;
+pub use union_find::{UnionFind, LabeledUnionFind};
 pub use two_dimensional::{Map, Coord};
-pub use graph::{Indexable, AdjList};
+pub use point::{Point, Point2, Point3, Scalar};
+pub use graph::AdjList;
+#[cfg(feature = "bigint")]
+pub use num_bigint::{BigInt, BigUint};
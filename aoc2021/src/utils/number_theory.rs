@@ -0,0 +1,435 @@
+use super::{AdventError, checked::Checked};
+use std::{fmt, iter::{Sum, Product}, ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, DivAssign, Neg}};
+
+pub fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, zero if one of the numbers is zero.
+pub fn lcm(a: usize, b: usize) -> usize {
+    if a == 0 || b == 0 {
+        return 0
+    }
+    a / gcd(a, b) * b
+}
+
+/// Least common multiple, which fails instead of wrapping around if it
+/// does not fit into a `usize`.
+pub fn try_lcm(a: usize, b: usize) -> Result<usize, AdventError> {
+    if a == 0 || b == 0 {
+        return Ok(0)
+    }
+    (a / gcd(a, b)).try_mul(&b)
+}
+
+/// Greatest common divisor of all numbers, zero for no numbers.
+pub fn gcd_all(numbers: impl IntoIterator<Item=usize>) -> usize {
+    numbers.into_iter().fold(0, gcd)
+}
+
+/// Least common multiple of all numbers, one for no numbers.
+///
+/// ```
+/// use aoc2021::utils::number_theory::lcm_all;
+///
+/// assert_eq!(lcm_all([4, 6, 10]), 60);
+/// ```
+pub fn lcm_all(numbers: impl IntoIterator<Item=usize>) -> usize {
+    numbers.into_iter().fold(1, lcm)
+}
+
+/// Least common multiple of all numbers, like `lcm_all`, but fails on overflow.
+pub fn try_lcm_all(numbers: impl IntoIterator<Item=usize>) -> Result<usize, AdventError> {
+    numbers.into_iter().try_fold(1, try_lcm)
+}
+
+/// Returns `(g, x, y)` with `a x + b y = g = gcd(a, b)`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `m`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: usize, m: usize) -> Option<usize> {
+    let (g, x, _) = extended_gcd(a as i128, m as i128);
+    (g == 1).then(|| x.rem_euclid(m as i128) as usize)
+}
+
+/// `base^exp mod m` by repeated squaring.
+pub fn mod_pow(base: usize, mut exp: usize, m: usize) -> usize {
+    let m = m as u128;
+    let mut base = base as u128 % m;
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as usize
+}
+
+/// Largest integer whose square is at most `n`.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n
+    }
+    // Newton's method started above the root decreases monotonically to its floor
+    let mut x = 1 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x
+        }
+        x = y;
+    }
+}
+
+/// The smallest and the largest integer `x` with `a x^2 + b x + c <= 0`, i.e.,
+/// the ceiling of the smaller and the floor of the larger root, or `None` if
+/// there is no integer between the roots. Exact without floating point.
+///
+/// For the strict inequality `< 0`, use `c + 1`. Fails with an overflow only
+/// for huge coefficients, any `b` within `u64` range is fine for small `a c`.
+///
+/// Panics, if `a` is not positive.
+///
+/// ```
+/// use aoc2021::utils::number_theory::quadratic_roots;
+///
+/// // roots at 2 and 3
+/// assert_eq!(quadratic_roots(1, -5, 6).unwrap(), Some((2, 3)));
+/// // roots at 1 +- sqrt(2)
+/// assert_eq!(quadratic_roots(1, -2, -1).unwrap(), Some((0, 2)));
+/// assert_eq!(quadratic_roots(4, -2, 0).unwrap(), Some((0, 0)));
+/// // roots at 1/4 and 3/4
+/// assert_eq!(quadratic_roots(16, -16, 3).unwrap(), None);
+/// ```
+pub fn quadratic_roots(a: i128, b: i128, c: i128) -> Result<Option<(i128, i128)>, AdventError> {
+    assert!(a > 0, "the quadratic has to open upwards");
+    // b^2 fits unsigned for all `|b| <= u64::MAX`
+    let b_squared = b.unsigned_abs().try_mul(&b.unsigned_abs())?;
+    let four_ac = a.try_mul(&c)?.try_mul(&4)?;
+    let discriminant = if four_ac < 0 {
+        b_squared.try_add(&four_ac.unsigned_abs())?
+    } else if four_ac.unsigned_abs() <= b_squared {
+        b_squared - four_ac.unsigned_abs()
+    } else {
+        return Ok(None)
+    };
+    // floor((m + sqrt(d)) / q) = floor((m + isqrt(d)) / q) for positive q
+    let s = isqrt(discriminant) as i128;
+    let q = a.try_mul(&2)?;
+    let low = 0.try_sub(&b.try_add(&s)?.div_euclid(q))?;
+    let high = s.try_sub(&b)?.div_euclid(q);
+    Ok((low <= high).then_some((low, high)))
+}
+
+/// Largest `k` with the triangular number `k (k + 1) / 2` at most `n`.
+///
+/// Panics, if `n` is at least `2^125`.
+///
+/// ```
+/// use aoc2021::utils::number_theory::triangular_root;
+///
+/// assert_eq!(triangular_root(9), 3);
+/// assert_eq!(triangular_root(10), 4);
+/// ```
+pub fn triangular_root(n: u128) -> u128 {
+    assert!(n < 1 << 125, "8 n + 1 has to fit");
+    // k (k + 1) / 2 <= n is equivalent to (2 k + 1)^2 <= 8 n + 1
+    (isqrt(8 * n + 1) - 1) / 2
+}
+
+/// Chinese remainder theorem: solves the system `n = residue mod modulus` for
+/// all given congruences. The moduli need not be coprime.
+///
+/// Returns the smallest non-negative solution and the least common multiple
+/// of the moduli, or `None` if the congruences contradict each other, a
+/// modulus is zero or the least common multiple overflows.
+///
+/// ```
+/// use aoc2021::utils::number_theory::crt;
+///
+/// assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+/// assert_eq!(crt([(0, 4), (1, 6)]), None);
+/// ```
+pub fn crt(congruences: impl IntoIterator<Item=(usize, usize)>) -> Option<(usize, usize)> {
+    let mut n: usize = 0;
+    let mut m: usize = 1;
+    for (b, k) in congruences {
+        if k == 0 {
+            return None
+        }
+        let (g, x, _) = extended_gcd(m as i128, k as i128);
+        let difference = (b % k) as i128 - n as i128;
+        if difference % g != 0 {
+            return None
+        }
+        let lcm = (m / g as usize).checked_mul(k)?;
+        // n + t m solves both, where t < k / g, such that n + t m < lcm
+        let step = k as i128 / g;
+        let t = (difference / g).rem_euclid(step) as u128 * x.rem_euclid(step) as u128 % step as u128;
+        n += t as usize * m;
+        m = lcm;
+    }
+    Some((n, m))
+}
+
+/// Residue class modulo `M`.
+///
+/// Division is only defined by elements coprime to `M`, e.g., if `M` is prime.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub fn new(value: i64) -> Self {
+        // in i128, as `M` may exceed `i64::MAX`
+        ModInt((value as i128).rem_euclid(M as i128) as u64)
+    }
+
+    /// The representative in `0..M`.
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(&self, exp: u64) -> Self {
+        ModInt(mod_pow(self.0 as usize, exp as usize, M as usize) as u64)
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        mod_inverse(self.0 as usize, M as usize).map(|i| ModInt(i as u64))
+    }
+}
+
+/// Residues cannot overflow.
+impl<const M: u64> Checked for ModInt<M> {
+    fn zero() -> Self {
+        ModInt(0)
+    }
+
+    fn one() -> Self {
+        ModInt::from(1)
+    }
+
+    fn try_add(&self, other: &Self) -> Result<Self, AdventError> {
+        Ok(*self + *other)
+    }
+
+    fn try_sub(&self, other: &Self) -> Result<Self, AdventError> {
+        Ok(*self - *other)
+    }
+
+    fn try_mul(&self, other: &Self) -> Result<Self, AdventError> {
+        Ok(*self * *other)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        ModInt(value % M)
+    }
+}
+
+impl<const M: u64> fmt::Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (mod {})", self.0, M)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        ModInt(((self.0 as u128 + rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        ModInt(((self.0 as u128 * rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    /// Panics, if `rhs` is not invertible.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inverse().expect("division by a non-invertible element")
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        if self.0 == 0 {self} else {ModInt(M - self.0)}
+    }
+}
+
+// the assigning operators are implemented in terms of the binary ones
+macro_rules! impl_assign_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl<const M: u64> $trait for ModInt<M> {
+            fn $method(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+    };
+}
+
+impl_assign_op!(AddAssign, add_assign, +);
+impl_assign_op!(SubAssign, sub_assign, -);
+impl_assign_op!(MulAssign, mul_assign, *);
+impl_assign_op!(DivAssign, div_assign, /);
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(ModInt(0), |a, b| a + b)
+    }
+}
+
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(ModInt::from(1), |a, b| a * b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn divisors() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(gcd_all([12, 18, 27]), 3);
+        assert_eq!(lcm_all([2, 3, 4, 5, 6]), 60);
+        assert_eq!(try_lcm_all([2, 3, 4, 5, 6]).unwrap(), 60);
+        assert_eq!(try_lcm(1 << 40, 1 << 50).unwrap(), 1 << 50);
+        assert!(matches!(try_lcm(1 << 40, (1 << 30) + 1), Err(AdventError::Overflow)));
+
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+    }
+
+    #[test]
+    fn modular() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(4, 12), None);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(3, 0, 1), 0);
+        assert_eq!(mod_pow(123456789, 987654321, 1_000_000_007), 652541198);
+
+        assert_eq!(crt([(0, 3), (3, 4), (4, 5)]), Some((39, 60)));
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(1, 0)]), None);
+
+        // the solution needs the full range of the moduli
+        let (p, q) = (u32::MAX as usize, 1 << 32);
+        let (n, m) = crt([(p - 1, p), (q - 1, q)]).unwrap();
+        assert_eq!((n % p, n % q, m), (p - 1, q - 1, p * q));
+        // the least common multiple of coprime moduli around 2^43 overflows
+        assert_eq!(crt([(1, (1 << 43) - 1), (2, 1 << 43), (3, (1 << 43) + 1)]), None);
+    }
+
+    #[test]
+    fn roots() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        let big = 1_000_000_000_000_000_000u128;
+        assert_eq!(isqrt(big * big - 1), big - 1);
+
+        // exactly at the boundary, where floating point is prone to be off by one
+        let n = 3_000_000_019i128;
+        assert_eq!(quadratic_roots(1, -2 * n, n * n).unwrap(), Some((n, n)));
+        assert_eq!(quadratic_roots(1, -2 * n, n * n + 1).unwrap(), None);
+        for (a, b, c) in [(1, 0, -10), (3, -7, -20), (2, 9, 4), (1, -30, 201)] {
+            let (low, high) = quadratic_roots(a, b, c).unwrap().unwrap();
+            let f = |x: i128| a * x * x + b * x + c;
+            assert!(f(low) <= 0 && f(high) <= 0);
+            assert!(f(low - 1) > 0 && f(high + 1) > 0);
+        }
+
+        for k in 0..100u128 {
+            let t = k * (k + 1) / 2;
+            assert_eq!(triangular_root(t), k);
+            assert_eq!(triangular_root(t + k), k);
+        }
+        assert_eq!(triangular_root((1 << 125) - 1), (1 << 63) - 1);
+
+        // the discriminant exceeds i128, but not u128
+        let m = u64::MAX as i128;
+        assert_eq!(quadratic_roots(1, -m, 0).unwrap(), Some((0, m)));
+        assert_eq!(quadratic_roots(1, -m, m).unwrap(), Some((2, m - 2)));
+        assert!(matches!(quadratic_roots(1, i128::MAX, 0), Err(AdventError::Overflow)));
+        assert!(matches!(quadratic_roots(i128::MAX, 0, -1), Err(AdventError::Overflow)));
+    }
+
+    #[test]
+    fn mod_int() {
+        type F = ModInt<7>;
+        let a = F::new(-2);
+        let b = F::from(10);
+        assert_eq!(a.value(), 5);
+        assert_eq!(b.value(), 3);
+        assert_eq!((a + b).value(), 1);
+        assert_eq!((a - b).value(), 2);
+        assert_eq!((a * b).value(), 1);
+        assert_eq!((a / b) * b, a);
+        assert_eq!(-F::new(0), F::new(0));
+        assert_eq!(b.pow(6), F::new(1));
+        assert_eq!((1..=6).map(F::from).product::<F>(), F::new(-1));
+
+        let mut c = a;
+        c *= b;
+        c -= F::new(1);
+        assert_eq!(c, F::new(0));
+        assert_eq!(format!("{a:?}"), "5 (mod 7)");
+
+        // the largest prime below 2^64
+        type G = ModInt<18446744073709551557>;
+        assert_eq!(G::new(-1).value(), 18446744073709551556);
+        assert_eq!(G::new(i64::MIN).value(), 18446744073709551557 - (1 << 63));
+        assert_eq!(G::new(-1) * G::new(-1), G::new(1));
+    }
+}
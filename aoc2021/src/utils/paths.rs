@@ -0,0 +1,262 @@
+use std::hash::Hash;
+
+use rustc_hash::FxHashMap;
+
+/// Decides whether a path may enter a node, which it might have visited before.
+pub trait VisitPolicy<C> {
+    /// Budget carried along a path, e.g., the number of remaining revisits.
+    type State: Clone + Hash + Eq;
+
+    fn initial(&self) -> Self::State;
+
+    /// Whether a path may enter `node`, which it has visited `visits` times
+    /// before. If it may, the returned state is carried on along the path.
+    fn enter(&self, node: &C, visits: usize, state: &Self::State) -> Option<Self::State>;
+
+    /// Whether the visits of `node` influence `enter`. Only tracked nodes
+    /// distinguish states during memoized counting.
+    fn tracks(&self, _node: &C) -> bool {
+        true
+    }
+}
+
+/// Every node may be visited at most once.
+#[derive(Debug, Clone, Copy)]
+pub struct SimplePaths;
+
+impl<C> VisitPolicy<C> for SimplePaths {
+    type State = ();
+
+    fn initial(&self) {}
+
+    fn enter(&self, _node: &C, visits: usize, _state: &()) -> Option<()> {
+        (visits == 0).then_some(())
+    }
+}
+
+/// Nodes for which `limited` holds may be visited once, all others arbitrarily
+/// often. Additionally, there are `revisits` jokers to enter a limited node again.
+#[derive(Debug, Clone, Copy)]
+pub struct Revisits<F> {
+    pub limited: F,
+    pub revisits: usize,
+}
+
+impl<F> Revisits<F> {
+    pub fn new(limited: F, revisits: usize) -> Revisits<F> {
+        Revisits {
+            limited,
+            revisits,
+        }
+    }
+}
+
+impl<C, F: Fn(&C) -> bool> VisitPolicy<C> for Revisits<F> {
+    type State = usize;
+
+    fn initial(&self) -> usize {
+        self.revisits
+    }
+
+    fn enter(&self, node: &C, visits: usize, &remaining: &usize) -> Option<usize> {
+        if visits == 0 || !(self.limited)(node) {
+            Some(remaining)
+        } else {
+            remaining.checked_sub(1)
+        }
+    }
+
+    fn tracks(&self, node: &C) -> bool {
+        (self.limited)(node)
+    }
+}
+
+/// Lazy enumeration of the paths from a start to an end node, see `all_paths`.
+pub struct Paths<C, S, I, P: VisitPolicy<C>> {
+    end: C,
+    successors: S,
+    policy: P,
+    path: Vec<C>,
+    visits: FxHashMap<C, usize>,
+    /// for every node of the path, its successors which are not yet tried
+    /// and the state of the policy after entering it
+    stack: Vec<(std::vec::IntoIter<C>, P::State)>,
+    _successors: std::marker::PhantomData<I>,
+}
+
+impl<C, S, I, P> Iterator for Paths<C, S, I, P>
+    where C: Hash + Eq + Clone, S: FnMut(&C) -> I, I: IntoIterator<Item=C>, P: VisitPolicy<C>
+{
+    type Item = Vec<C>;
+
+    fn next(&mut self) -> Option<Vec<C>> {
+        while let Some((candidates, state)) = self.stack.last_mut() {
+            let Some(node) = candidates.next() else {
+                // all continuations are exhausted, backtrack
+                self.stack.pop();
+                let left = self.path.pop().unwrap();
+                *self.visits.get_mut(&left).unwrap() -= 1;
+                continue;
+            };
+
+            if node == self.path[0] {
+                continue;
+            }
+            let visits = self.visits.get(&node).cloned().unwrap_or(0);
+            let Some(state) = self.policy.enter(&node, visits, state) else {
+                continue;
+            };
+
+            if node == self.end {
+                let mut path = self.path.clone();
+                path.push(node);
+                return Some(path)
+            }
+
+            *self.visits.entry(node.clone()).or_insert(0) += 1;
+            let candidates = (self.successors)(&node).into_iter().collect::<Vec<_>>().into_iter();
+            self.path.push(node);
+            self.stack.push((candidates, state));
+        }
+
+        None
+    }
+}
+
+/// Enumerates all paths from `start` to `end`, which the `policy` allows.
+///
+/// Paths never return to `start` and stop at their first arrival at `end`.
+/// If the policy allows to revisit nodes, which are on a cycle of nodes the
+/// policy does not limit, there are infinitely many paths.
+pub fn all_paths<C, S, I, P>(start: C, end: C, mut successors: S, policy: P) -> Paths<C, S, I, P>
+    where C: Hash + Eq + Clone, S: FnMut(&C) -> I, I: IntoIterator<Item=C>, P: VisitPolicy<C>
+{
+    assert!(start != end, "start and end have to differ");
+    let candidates = successors(&start).into_iter().collect::<Vec<_>>().into_iter();
+    let state = policy.initial();
+
+    Paths {
+        end,
+        successors,
+        policy,
+        path: vec![start.clone()],
+        visits: FxHashMap::from_iter([(start, 1)]),
+        stack: vec![(candidates, state)],
+        _successors: std::marker::PhantomData,
+    }
+}
+
+/// Counts the paths `all_paths` would enumerate.
+///
+/// Paths reaching the same node with the same visited tracked nodes and the
+/// same policy state have the same continuations, which are counted only once.
+pub fn count_paths<C, S, I, P>(start: C, end: C, mut successors: S, policy: P) -> usize
+    where C: Hash + Ord + Clone, S: FnMut(&C) -> I, I: IntoIterator<Item=C>, P: VisitPolicy<C>
+{
+    type Key<C, T> = (C, T, Vec<(C, usize)>);
+
+    struct Counter<'a, C, S, P: VisitPolicy<C>> {
+        start: C,
+        end: C,
+        successors: S,
+        policy: P,
+        visits: FxHashMap<C, usize>,
+        cache: &'a mut FxHashMap<Key<C, P::State>, usize>,
+    }
+
+    impl<C, S, I, P> Counter<'_, C, S, P>
+        where C: Hash + Ord + Clone, S: FnMut(&C) -> I, I: IntoIterator<Item=C>, P: VisitPolicy<C>
+    {
+        fn count(&mut self, node: C, state: P::State) -> usize {
+            if node == self.end {
+                return 1
+            }
+
+            let mut visited: Vec<(C, usize)> = self.visits.iter()
+                .filter(|&(n, &v)| v > 0 && self.policy.tracks(n))
+                .map(|(n, &v)| (n.clone(), v))
+                .collect();
+            visited.sort();
+            let key = (node.clone(), state.clone(), visited);
+            if let Some(&count) = self.cache.get(&key) {
+                return count
+            }
+
+            let mut count = 0;
+            let candidates: Vec<C> = (self.successors)(&node).into_iter().collect();
+            for n in candidates {
+                if n == self.start {
+                    continue;
+                }
+                let visits = self.visits.get(&n).cloned().unwrap_or(0);
+                if let Some(next) = self.policy.enter(&n, visits, &state) {
+                    *self.visits.entry(n.clone()).or_insert(0) += 1;
+                    count += self.count(n.clone(), next);
+                    *self.visits.get_mut(&n).unwrap() -= 1;
+                }
+            }
+
+            self.cache.insert(key, count);
+            count
+        }
+    }
+
+    assert!(start != end, "start and end have to differ");
+    let mut cache = FxHashMap::default();
+    let state = policy.initial();
+    let mut counter = Counter {
+        start: start.clone(),
+        end,
+        successors: &mut successors,
+        policy,
+        visits: FxHashMap::from_iter([(start.clone(), 1)]),
+        cache: &mut cache,
+    };
+    counter.count(start, state)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{AdjList, shortest_path::Neighborful};
+
+    use super::*;
+
+    #[test]
+    fn simple() {
+        // a square with a diagonal
+        let mut g = AdjList::undirected();
+        for (s, t) in [(0, 1), (1, 2), (2, 3), (3, 0), (0, 2)] {
+            g.add_edge(s, t);
+        }
+        let (start, end) = (g.id(&0).unwrap(), g.id(&2).unwrap());
+
+        let mut paths: Vec<Vec<usize>> = all_paths(start, end, |&n| g.neighbors(n), SimplePaths)
+            .map(|p| p.iter().map(|&n| g[n]).collect())
+            .collect();
+        paths.sort();
+        assert_eq!(paths, vec![vec![0, 1, 2], vec![0, 2], vec![0, 3, 2]]);
+        assert_eq!(count_paths(start, end, |&n| g.neighbors(n), SimplePaths), 3);
+    }
+
+    #[test]
+    fn revisits() {
+        // a line 0 - 1 - 2 with a loop at 1 via 3
+        let mut g = AdjList::undirected();
+        for (s, t) in [(0, 1), (1, 2), (1, 3)] {
+            g.add_edge(s, t);
+        }
+        let (start, end) = (g.id(&0).unwrap(), g.id(&2).unwrap());
+        let successors = |&n: &usize| g.neighbors(n).collect::<Vec<_>>();
+
+        // 3 is a dead end, unless 1 can be visited twice
+        assert_eq!(all_paths(start, end, successors, Revisits::new(|_: &usize| true, 0)).count(), 1);
+        let twice: Vec<Vec<usize>> = all_paths(start, end, successors, Revisits::new(|_: &usize| true, 1)).collect();
+        assert_eq!(twice.len(), 2);
+        assert!(twice.contains(&vec![start, g.id(&1).unwrap(), g.id(&3).unwrap(), g.id(&1).unwrap(), end]));
+
+        // only 3 is limited, so 1 is free, but 3 may be visited twice
+        let only_three = |&n: &usize| g[n] == 3;
+        assert_eq!(all_paths(start, end, successors, Revisits::new(only_three, 1)).count(), 3);
+        assert_eq!(count_paths(start, end, successors, Revisits::new(only_three, 1)), 3);
+    }
+}
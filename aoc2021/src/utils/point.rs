@@ -0,0 +1,365 @@
+use std::{fmt, str::FromStr, iter::Sum, ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Neg, Index, IndexMut}};
+
+use super::{AdventError, Coord};
+
+/// Numeric types which can be used as coordinates of a `Point`.
+pub trait Scalar:
+    Copy + PartialOrd + Sum
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+    + AddAssign + SubAssign + MulAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// `|self - other|`, which does not underflow for unsigned types.
+    fn abs_sub(self, other: Self) -> Self;
+    fn signum(self) -> Self;
+    fn as_f64(self) -> f64;
+}
+
+macro_rules! impl_scalar_signed {
+    ($($t:ty)*) => {$(
+        impl Scalar for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn abs_sub(self, other: Self) -> Self {
+                (self - other).abs()
+            }
+
+            fn signum(self) -> Self {
+                <$t>::signum(self)
+            }
+
+            fn as_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*}
+}
+
+macro_rules! impl_scalar_unsigned {
+    ($($t:ty)*) => {$(
+        impl Scalar for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn abs_sub(self, other: Self) -> Self {
+                self.abs_diff(other)
+            }
+
+            fn signum(self) -> Self {
+                if self == 0 {0} else {1}
+            }
+
+            fn as_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*}
+}
+
+impl_scalar_signed!{i8 i16 i32 i64 i128 isize}
+impl_scalar_unsigned!{u8 u16 u32 u64 u128 usize}
+
+/// A point (or vector) in `N` dimensions with coordinates of type `T`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Point<T, const N: usize> {
+    pub coords: [T; N],
+}
+
+pub type Point2 = Point<isize, 2>;
+pub type Point3 = Point<isize, 3>;
+
+impl<T, const N: usize> Point<T, N> {
+    pub fn from_array(coords: [T; N]) -> Self {
+        Point { coords }
+    }
+}
+
+impl<T: Copy> Point<T, 2> {
+    pub fn new(x: T, y: T) -> Self {
+        Point { coords: [x, y] }
+    }
+
+    pub fn x(&self) -> T {
+        self.coords[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.coords[1]
+    }
+}
+
+impl<T: Copy> Point<T, 3> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Point { coords: [x, y, z] }
+    }
+
+    pub fn x(&self) -> T {
+        self.coords[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.coords[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.coords[2]
+    }
+}
+
+impl<T: Scalar, const N: usize> Point<T, N> {
+    pub fn zero() -> Self {
+        Point { coords: [T::ZERO; N] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coords.iter().all(|&c| c == T::ZERO)
+    }
+
+    /// L1 norm of the difference, i.e., the number of orthogonal steps between the points
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.coords.iter().zip(other.coords.iter())
+            .map(|(&a, &b)| a.abs_sub(b))
+            .sum()
+    }
+
+    /// L∞ norm of the difference, i.e., the number of king moves between the points
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.coords.iter().zip(other.coords.iter())
+            .map(|(&a, &b)| a.abs_sub(b))
+            .fold(T::ZERO, |max, d| if d > max {d} else {max})
+    }
+
+    pub fn euclidean(&self, other: &Self) -> f64 {
+        self.coords.iter().zip(other.coords.iter())
+            .map(|(&a, &b)| a.abs_sub(b).as_f64().powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Componentwise signum, e.g., `(b - a).signum()` is the king move from `a` towards `b`.
+    pub fn signum(&self) -> Self {
+        Point { coords: self.coords.map(T::signum) }
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.coords.iter().zip(other.coords.iter())
+            .map(|(&a, &b)| a * b)
+            .sum()
+    }
+}
+
+impl<T: TryInto<usize> + Copy> Point<T, 2> {
+    /// Converts to a grid coordinate of a `Map`, if both coordinates are non-negative.
+    pub fn to_coord(&self) -> Option<Coord> {
+        Some((self.x().try_into().ok()?, self.y().try_into().ok()?))
+    }
+}
+
+impl<T: TryFrom<usize>> Point<T, 2> {
+    pub fn from_coord((x, y): Coord) -> Option<Self> {
+        Some(Point { coords: [x.try_into().ok()?, y.try_into().ok()?] })
+    }
+}
+
+impl From<Coord> for Point<usize, 2> {
+    fn from((x, y): Coord) -> Self {
+        Point { coords: [x, y] }
+    }
+}
+
+impl From<Point<usize, 2>> for Coord {
+    fn from(p: Point<usize, 2>) -> Self {
+        (p.coords[0], p.coords[1])
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Point<T, N> {
+    fn from(coords: [T; N]) -> Self {
+        Point { coords }
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Point<T, N> {
+    type Output = T;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.coords[idx]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Point<T, N> {
+    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+        &mut self.coords[idx]
+    }
+}
+
+// implement the binary operators for all combinations of owned values and references
+macro_rules! impl_vector_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl<T: Scalar, const N: usize> $trait for Point<T, N> {
+            type Output = Point<T, N>;
+
+            fn $method(self, rhs: Self) -> Self::Output {
+                Point { coords: std::array::from_fn(|i| self.coords[i] $op rhs.coords[i]) }
+            }
+        }
+
+        impl<T: Scalar, const N: usize> $trait<&Point<T, N>> for Point<T, N> {
+            type Output = Point<T, N>;
+
+            fn $method(self, rhs: &Point<T, N>) -> Self::Output {
+                self $op *rhs
+            }
+        }
+
+        impl<T: Scalar, const N: usize> $trait<Point<T, N>> for &Point<T, N> {
+            type Output = Point<T, N>;
+
+            fn $method(self, rhs: Point<T, N>) -> Self::Output {
+                *self $op rhs
+            }
+        }
+
+        impl<T: Scalar, const N: usize> $trait for &Point<T, N> {
+            type Output = Point<T, N>;
+
+            fn $method(self, rhs: Self) -> Self::Output {
+                *self $op *rhs
+            }
+        }
+
+        impl<T: Scalar, const N: usize> $assign_trait for Point<T, N> {
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+
+        impl<T: Scalar, const N: usize> $assign_trait<&Point<T, N>> for Point<T, N> {
+            fn $assign_method(&mut self, rhs: &Point<T, N>) {
+                *self = *self $op *rhs;
+            }
+        }
+    };
+}
+
+impl_vector_op!(Add, add, AddAssign, add_assign, +);
+impl_vector_op!(Sub, sub, SubAssign, sub_assign, -);
+
+/// Multiplication with a scalar
+impl<T: Scalar, const N: usize> Mul<T> for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point { coords: self.coords.map(|c| c * rhs) }
+    }
+}
+
+impl<T: Scalar, const N: usize> Mul<T> for &Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        *self * rhs
+    }
+}
+
+impl<T: Scalar, const N: usize> MulAssign<T> for Point<T, N> {
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: Scalar + Neg<Output = T>, const N: usize> Neg for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn neg(self) -> Self::Output {
+        Point { coords: self.coords.map(|c| -c) }
+    }
+}
+
+impl<T: Scalar + Neg<Output = T>, const N: usize> Neg for &Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn neg(self) -> Self::Output {
+        -*self
+    }
+}
+
+impl<T: fmt::Display, const N: usize> fmt::Display for Point<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (n, c) in self.coords.iter().enumerate() {
+            if n > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{c}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses comma separated coordinates, e.g., `3,-4,5`.
+impl<T: FromStr<Err = std::num::ParseIntError>, const N: usize> FromStr for Point<T, N> {
+    type Err = AdventError;
+
+    fn from_str(input: &str) -> Result<Self, AdventError> {
+        let values = input.trim()
+            .split(',')
+            .map(|i| i.trim().parse())
+            .collect::<Result<Vec<T>, _>>()?;
+
+        let coords = values.try_into()
+            .map_err(|_| AdventError::WrongNumberOfElements)?;
+
+        Ok(Point { coords })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(-3, 5);
+
+        assert_eq!(a + b, Point2::new(-2, 3));
+        assert_eq!(a - b, Point2::new(4, -7));
+        assert_eq!(a * 3, Point2::new(3, -6));
+        assert_eq!(-a, Point2::new(-1, 2));
+        assert_eq!((b - a).signum(), Point2::new(-1, 1));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point3::new(1, -2, 0);
+        let b = Point3::new(-3, 5, 0);
+
+        assert_eq!(a.manhattan(&b), 11);
+        assert_eq!(a.chebyshev(&b), 7);
+        assert!((a.euclidean(&b) - 65f64.sqrt()).abs() < 1e-12);
+
+        let u = Point::<usize, 2>::new(2, 7);
+        let v = Point::<usize, 2>::new(5, 3);
+        assert_eq!(u.manhattan(&v), 7);
+    }
+
+    #[test]
+    fn conversions() {
+        let p: Point<isize, 3> = "404,-588,-901".parse().unwrap();
+        assert_eq!(p, Point3::new(404, -588, -901));
+        assert_eq!(p.to_string(), "404,-588,-901");
+        assert!("1,2".parse::<Point3>().is_err());
+
+        assert_eq!(Point2::new(3, 4).to_coord(), Some((3, 4)));
+        assert_eq!(Point2::new(-1, 4).to_coord(), None);
+        assert_eq!(Point2::from_coord((3, 4)), Some(Point2::new(3, 4)));
+    }
+}
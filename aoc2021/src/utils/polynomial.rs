@@ -0,0 +1,224 @@
+use super::rational::Rational;
+
+/// Polynomial through equidistant samples in the Newton forward difference form
+/// `f(n) = sum_k Δ^k f(0) binom(n, k)`, which stays in integers.
+///
+/// ```
+/// use aoc2021::utils::polynomial::Newton;
+///
+/// let squares = Newton::from_sequence(&[0, 1, 4, 9]);
+/// assert_eq!(squares.degree(), 2);
+/// assert_eq!(squares.eval(10), 100);
+/// assert_eq!(squares.eval(-3), 9);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Newton {
+    /// `Δ^k f(0)` for all orders `k`
+    leading: Vec<i128>,
+}
+
+impl Newton {
+    /// The polynomial of lowest degree with `f(n) = values[n]`.
+    pub fn from_sequence(values: &[i64]) -> Newton {
+        let mut row: Vec<i128> = values.iter().map(|&v| v as i128).collect();
+        let mut leading = Vec::with_capacity(row.len());
+        while let Some(&first) = row.first() {
+            leading.push(first);
+            if row.iter().all(|&v| v == 0) {
+                break
+            }
+            row = row.windows(2).map(|w| w[1] - w[0]).collect();
+        }
+        while leading.len() > 1 && leading.last() == Some(&0) {
+            leading.pop();
+        }
+        Newton { leading }
+    }
+
+    /// The leading entries of the difference table, i.e., `Δ^k f(0)`.
+    pub fn differences(&self) -> &[i128] {
+        &self.leading
+    }
+
+    /// Degree of the polynomial, where the zero polynomial has degree 0.
+    pub fn degree(&self) -> usize {
+        self.leading.len().saturating_sub(1)
+    }
+
+    /// Value at any, also negative, integer `n`.
+    pub fn eval(&self, n: i64) -> i128 {
+        let n = n as i128;
+        let mut binomial = 1;
+        let mut sum = 0;
+        for (k, &d) in self.leading.iter().enumerate() {
+            sum += d * binomial;
+            // binom(n, k + 1) = binom(n, k) (n - k) / (k + 1) is exact
+            binomial = binomial * (n - k as i128) / (k as i128 + 1);
+        }
+        sum
+    }
+
+    pub fn to_polynomial(&self) -> Polynomial {
+        let mut result = Polynomial::zero();
+        // binom(x, k) as a polynomial in x
+        let mut binomial = Polynomial::constant(Rational::ONE);
+        for (k, &d) in self.leading.iter().enumerate() {
+            result = result.add(&binomial.scale(Rational::from(d)));
+            let k = Rational::from(k as i128);
+            binomial = binomial.mul(&Polynomial::new(vec![-k, Rational::ONE]))
+                .scale((k + Rational::ONE).recip());
+        }
+        result
+    }
+}
+
+/// Degree of the polynomial generating `values` at `0, 1, 2, ...`, if the
+/// sequence is long enough to confirm it, i.e., has at least `degree + 2` elements.
+///
+/// ```
+/// use aoc2021::utils::polynomial::degree;
+///
+/// assert_eq!(degree(&[1, 3, 6, 10, 15]), Some(2));
+/// assert_eq!(degree(&[1, 2, 4, 8, 16]), None);
+/// ```
+pub fn degree(values: &[i64]) -> Option<usize> {
+    let degree = Newton::from_sequence(values).degree();
+    (degree + 2 <= values.len()).then_some(degree)
+}
+
+/// Polynomial with exact rational coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    /// coefficients in ascending order of the power, without trailing zeros
+    coefficients: Vec<Rational>,
+}
+
+impl Polynomial {
+    pub fn new(mut coefficients: Vec<Rational>) -> Polynomial {
+        while coefficients.last() == Some(&Rational::ZERO) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    pub fn zero() -> Polynomial {
+        Polynomial { coefficients: Vec::new() }
+    }
+
+    pub fn constant(c: Rational) -> Polynomial {
+        Polynomial::new(vec![c])
+    }
+
+    /// The polynomial of lowest degree through `values` at `0, 1, 2, ...`.
+    pub fn from_sequence(values: &[i64]) -> Polynomial {
+        Newton::from_sequence(values).to_polynomial()
+    }
+
+    /// The Lagrange polynomial through all given points, which need distinct `x`.
+    ///
+    /// ```
+    /// use aoc2021::utils::{polynomial::Polynomial, rational::Rational};
+    ///
+    /// let points = [(-1, 2), (1, 0), (3, 2)].map(|(x, y)| (Rational::from(x as i64), Rational::from(y as i64)));
+    /// let p = Polynomial::lagrange(&points);
+    /// assert_eq!(p.coefficients(), &[Rational::new(1, 2), Rational::from(-1i64), Rational::new(1, 2)]);
+    /// ```
+    pub fn lagrange(points: &[(Rational, Rational)]) -> Polynomial {
+        let mut result = Polynomial::zero();
+        for (i, &(xi, yi)) in points.iter().enumerate() {
+            let mut basis = Polynomial::constant(yi);
+            for (j, &(xj, _)) in points.iter().enumerate() {
+                if i != j {
+                    assert!(xi != xj, "interpolation points need distinct x");
+                    basis = basis.mul(&Polynomial::new(vec![-xj, Rational::ONE]))
+                        .scale((xi - xj).recip());
+                }
+            }
+            result = result.add(&basis);
+        }
+        result
+    }
+
+    /// Coefficients in ascending order of the power.
+    pub fn coefficients(&self) -> &[Rational] {
+        &self.coefficients
+    }
+
+    /// Degree of the polynomial, where the zero polynomial has degree 0.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    pub fn eval(&self, x: Rational) -> Rational {
+        self.coefficients.iter().rev()
+            .fold(Rational::ZERO, |acc, &c| acc * x + c)
+    }
+
+    pub fn add(&self, other: &Polynomial) -> Polynomial {
+        let n = self.coefficients.len().max(other.coefficients.len());
+        let coefficient = |p: &Polynomial, i| p.coefficients.get(i).copied().unwrap_or_default();
+        Polynomial::new((0..n).map(|i| coefficient(self, i) + coefficient(other, i)).collect())
+    }
+
+    pub fn mul(&self, other: &Polynomial) -> Polynomial {
+        if self.coefficients.is_empty() || other.coefficients.is_empty() {
+            return Polynomial::zero()
+        }
+        let mut coefficients = vec![Rational::ZERO; self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            for (j, &b) in other.coefficients.iter().enumerate() {
+                coefficients[i + j] += a * b;
+            }
+        }
+        Polynomial::new(coefficients)
+    }
+
+    pub fn scale(&self, factor: Rational) -> Polynomial {
+        Polynomial::new(self.coefficients.iter().map(|&c| c * factor).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newton() {
+        let triangular = Newton::from_sequence(&[0, 1, 3, 6, 10]);
+        assert_eq!(triangular.differences(), &[0, 1, 1]);
+        assert_eq!(triangular.eval(100), 5050);
+        assert_eq!(triangular.eval(-1), 0);
+        assert_eq!(triangular.eval(-5), 10);
+
+        let zero = Newton::from_sequence(&[0, 0, 0]);
+        assert_eq!(zero.degree(), 0);
+        assert_eq!(zero.eval(7), 0);
+        assert_eq!(degree(&[0, 0, 0]), Some(0));
+        assert_eq!(degree(&[5]), None);
+
+        // a cubic needs five values to be confirmed
+        let cubes = [0, 1, 8, 27, 64];
+        assert_eq!(degree(&cubes), Some(3));
+        assert_eq!(degree(&cubes[..4]), None);
+        assert_eq!(Newton::from_sequence(&cubes).eval(-2), -8);
+    }
+
+    #[test]
+    fn coefficients() {
+        // n (n + 1) / 2
+        let p = Polynomial::from_sequence(&[0, 1, 3, 6]);
+        assert_eq!(p.coefficients(), &[Rational::ZERO, Rational::new(1, 2), Rational::new(1, 2)]);
+        assert_eq!(p.eval(Rational::new(1, 2)), Rational::new(3, 8));
+
+        // 2 x^3 - x + 5
+        let f = |x: i64| 2 * x.pow(3) - x + 5;
+        let points: Vec<_> = [-2, 0, 3, 7].into_iter()
+            .map(|x| (Rational::from(x), Rational::from(f(x))))
+            .collect();
+        let q = Polynomial::lagrange(&points);
+        assert_eq!(q.degree(), 3);
+        assert_eq!(q, Polynomial::from_sequence(&[f(0), f(1), f(2), f(3)]));
+        assert_eq!(q.eval(Rational::from(-10i64)), Rational::from(f(-10)));
+        assert_eq!(Polynomial::lagrange(&[]), Polynomial::zero());
+    }
+}
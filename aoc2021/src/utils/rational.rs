@@ -0,0 +1,338 @@
+use super::{AdventError, checked::Checked};
+use std::{fmt, cmp::Ordering, iter::{Sum, Product}, ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, DivAssign, Neg}};
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Compares `a.0 / a.1` and `b.0 / b.1` with positive denominators. If the
+/// cross products overflow, the integer parts are compared and the fractional
+/// parts by their reciprocals, i.e., along the continued fractions.
+fn cmp_fractions(mut a: (i128, i128), mut b: (i128, i128)) -> Ordering {
+    let mut reversed = false;
+    loop {
+        let ordering = if let (Some(x), Some(y)) = (a.0.checked_mul(b.1), b.0.checked_mul(a.1)) {
+            x.cmp(&y)
+        } else {
+            let (int_a, frac_a) = (a.0.div_euclid(a.1), a.0.rem_euclid(a.1));
+            let (int_b, frac_b) = (b.0.div_euclid(b.1), b.0.rem_euclid(b.1));
+            if int_a != int_b || frac_a == 0 || frac_b == 0 {
+                int_a.cmp(&int_b).then(frac_a.cmp(&frac_b))
+            } else {
+                // frac_a / a.1 < frac_b / b.1, if a.1 / frac_a > b.1 / frac_b
+                a = (a.1, frac_a);
+                b = (b.1, frac_b);
+                reversed = !reversed;
+                continue
+            }
+        };
+        return if reversed {ordering.reverse()} else {ordering}
+    }
+}
+
+/// Exact fraction in lowest terms with a positive denominator.
+///
+/// The operators panic on overflow, the methods of `Checked` report it as an error.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { numer: 0, denom: 1 };
+    pub const ONE: Rational = Rational { numer: 1, denom: 1 };
+
+    /// Panics, if `denom` is zero.
+    pub fn new(numer: i128, denom: i128) -> Rational {
+        assert!(denom != 0, "denominator of zero");
+        let g = gcd(numer.unsigned_abs(), denom.unsigned_abs());
+        // the gcd is 2^127 only if both are i128::MIN or one of them is zero
+        let (numer, denom) = match i128::try_from(g) {
+            Ok(g) => (numer / g, denom / g),
+            Err(_) => (numer.signum(), denom.signum()),
+        };
+        if denom < 0 {
+            let overflow = "overflow in rational arithmetic";
+            Rational { numer: numer.checked_neg().expect(overflow), denom: denom.checked_neg().expect(overflow) }
+        } else {
+            Rational { numer, denom }
+        }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.denom
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == 1
+    }
+
+    /// The value, if it is an integer.
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.numer)
+    }
+
+    pub fn floor(&self) -> i128 {
+        self.numer.div_euclid(self.denom)
+    }
+
+    pub fn ceil(&self) -> i128 {
+        self.floor() + (self.numer.rem_euclid(self.denom) != 0) as i128
+    }
+
+    pub fn recip(&self) -> Rational {
+        Rational::new(self.denom, self.numer)
+    }
+
+    /// Panics on overflow.
+    pub fn pow(&self, exp: u32) -> Rational {
+        self.try_pow(exp).expect("overflow in rational arithmetic")
+    }
+
+    /// `self^exp` by repeated squaring, fails on overflow.
+    pub fn try_pow(&self, mut exp: u32) -> Result<Rational, AdventError> {
+        let mut result = Rational::ONE;
+        let mut base = *self;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.try_mul(&base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.try_mul(&base)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Checked for Rational {
+    fn zero() -> Self {
+        Rational::ZERO
+    }
+
+    fn one() -> Self {
+        Rational::ONE
+    }
+
+    fn try_add(&self, other: &Self) -> Result<Self, AdventError> {
+        // dividing by the common factor first keeps the intermediates small
+        let g = gcd(self.denom as u128, other.denom as u128) as i128;
+        let numer = self.numer.checked_mul(other.denom / g)
+            .zip(other.numer.checked_mul(self.denom / g))
+            .and_then(|(a, b)| a.checked_add(b));
+        let denom = (self.denom / g).checked_mul(other.denom);
+        match (numer, denom) {
+            (Some(n), Some(d)) => Ok(Rational::new(n, d)),
+            _ => Err(AdventError::Overflow),
+        }
+    }
+
+    fn try_sub(&self, other: &Self) -> Result<Self, AdventError> {
+        let negated = Rational { numer: other.numer.checked_neg().ok_or(AdventError::Overflow)?, denom: other.denom };
+        self.try_add(&negated)
+    }
+
+    fn try_mul(&self, other: &Self) -> Result<Self, AdventError> {
+        // cancel crosswise, such that the products are already in lowest terms
+        let g1 = gcd(self.numer.unsigned_abs(), other.denom as u128).max(1) as i128;
+        let g2 = gcd(other.numer.unsigned_abs(), self.denom as u128).max(1) as i128;
+        let numer = (self.numer / g1).checked_mul(other.numer / g2);
+        let denom = (self.denom / g2).checked_mul(other.denom / g1);
+        match (numer, denom) {
+            (Some(n), Some(d)) => Ok(Rational::new(n, d)),
+            _ => Err(AdventError::Overflow),
+        }
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Rational::ZERO
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Rational { numer: value, denom: 1 }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Rational::from(value as i128)
+    }
+}
+
+impl From<isize> for Rational {
+    fn from(value: isize) -> Self {
+        Rational::from(value as i128)
+    }
+}
+
+impl fmt::Debug for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.denom == 1 {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_fractions((self.numer, self.denom), (other.numer, other.denom))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.try_add(&rhs).expect("overflow in rational arithmetic")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.try_sub(&rhs).expect("overflow in rational arithmetic")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.try_mul(&rhs).expect("overflow in rational arithmetic")
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    /// Panics, if `rhs` is zero.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.recip()
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Rational { numer: self.numer.checked_neg().expect("overflow in rational arithmetic"), denom: self.denom }
+    }
+}
+
+macro_rules! impl_assign_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl $trait for Rational {
+            fn $method(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+    };
+}
+
+impl_assign_op!(AddAssign, add_assign, +);
+impl_assign_op!(SubAssign, sub_assign, -);
+impl_assign_op!(MulAssign, mul_assign, *);
+impl_assign_op!(DivAssign, div_assign, /);
+
+impl Sum for Rational {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Rational::ZERO, |a, b| a + b)
+    }
+}
+
+impl Product for Rational {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Rational::ONE, |a, b| a * b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Rational::new(6, -4);
+        assert_eq!((a.numer(), a.denom()), (-3, 2));
+        let b = Rational::new(1, 3);
+        assert_eq!(a + b, Rational::new(-7, 6));
+        assert_eq!(a - b, Rational::new(-11, 6));
+        assert_eq!(a * b, Rational::new(-1, 2));
+        assert_eq!(a / b, Rational::new(-9, 2));
+        assert_eq!((a / b).floor(), -5);
+        assert_eq!((a / b).ceil(), -4);
+        assert!(a < b && -a > b);
+        assert_eq!(format!("{a}"), "-3/2");
+        assert_eq!((a * Rational::from(2i64)).to_integer(), Some(-3));
+        assert_eq!((1..=4).map(|n| Rational::new(1, n)).sum::<Rational>(), Rational::new(25, 12));
+    }
+
+    #[test]
+    fn overflow() {
+        let big = Rational::new(i128::MAX, 3);
+        assert!(matches!(big.try_mul(&Rational::from(4i128)), Err(AdventError::Overflow)));
+        // crosswise cancellation avoids the overflow of the naive product
+        assert_eq!(big.try_mul(&Rational::new(3, i128::MAX)).unwrap(), Rational::ONE);
+        assert!(big.try_add(&big).is_err());
+        // common denominators are not multiplied
+        let tiny = Rational::new(1, i128::MAX);
+        assert_eq!(tiny.try_add(&tiny).unwrap(), Rational::new(2, i128::MAX));
+        assert!(Rational::from(i128::MAX).try_add(&Rational::ONE).is_err());
+
+        assert_eq!(Rational::from(2i128).try_pow(126).unwrap(), Rational::from(1i128 << 126));
+        assert!(matches!(Rational::from(2i128).try_pow(127), Err(AdventError::Overflow)));
+        assert_eq!(Rational::new(-2, 3).pow(3), Rational::new(-8, 27));
+        assert_eq!(Rational::new(i128::MIN, i128::MIN), Rational::ONE);
+        assert_eq!(Rational::new(i128::MIN + 1, 2).ceil(), -(1 << 126) + 1);
+    }
+
+    #[test]
+    fn order_near_overflow() {
+        // the cross products overflow: x / (x - 1) = 1 + 1 / (x - 1) < 1 + 1 / (x - 2)
+        let x = i128::MAX;
+        let a = Rational::new(x, x - 1);
+        let b = Rational::new(x - 1, x - 2);
+        assert!(a < b);
+        assert!(-a > -b);
+        assert!(Rational::new(x, 3) > Rational::new(x - 1, 3));
+        assert!(Rational::new(x, 2) > Rational::new(x - 2, 3));
+        assert_eq!(a.cmp(&a), Ordering::Equal);
+        assert!(Rational::new(x - 1, x) > Rational::new(x - 2, x - 1));
+        assert!(Rational::new(-x, x - 1) > Rational::new(-(x - 1), x - 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn negate_min() {
+        let _ = -Rational::from(i128::MIN);
+    }
+}
@@ -0,0 +1,255 @@
+use std::hash::Hash;
+
+use rustc_hash::FxHashSet;
+
+use super::{Map, Coord, shortest_path::Neighborful};
+
+/// Finds all nodes reachable from `start` via passable nodes.
+///
+/// The nodes are returned in depth first order, which means that on a cycle
+/// they are ordered along the cycle.
+pub fn flood_fill<G, C>(graph: &G, start: C, passable: impl Fn(C) -> bool) -> Vec<C>
+    where G: Neighborful<C>, C: Hash + Eq + Copy
+{
+    let mut visited = FxHashSet::default();
+    let mut out = Vec::new();
+    let mut candidates = vec![start];
+
+    while let Some(current) = candidates.pop() {
+        if !passable(current) || !visited.insert(current) {
+            continue;
+        }
+        out.push(current);
+        candidates.extend(graph.neighbors(current).filter(|n| !visited.contains(n)));
+    }
+
+    out
+}
+
+/// A connected region of a `Map`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub size: usize,
+    /// number of edges between sites of the region and sites outside of it (or the border)
+    pub perimeter: usize,
+    /// upper left and lower right corner of the bounding box (inclusive)
+    pub bounding_box: (Coord, Coord),
+}
+
+/// Labeling of the connected components of a `Map`.
+pub struct Labels {
+    /// index into `regions` for every site which is part of a component
+    pub labels: Map<Option<usize>>,
+    pub regions: Vec<Region>,
+}
+
+impl Labels {
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    pub fn sizes(&self) -> impl Iterator<Item=usize> + '_ {
+        self.regions.iter().map(|r| r.size)
+    }
+
+    /// The sites of the component with the given label, in row major order.
+    pub fn sites(&self, label: usize) -> impl Iterator<Item=Coord> + '_ {
+        let (width, height) = (self.labels.width, self.labels.height);
+        (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+            .filter(move |&c| self.labels[c] == Some(label))
+    }
+}
+
+impl<T> Map<T> {
+    /// Finds all sites which are orthogonally connected to `start` via sites
+    /// whose values are `passable`.
+    pub fn flood_fill(&self, start: Coord, passable: impl Fn(&T) -> bool) -> Vec<Coord> {
+        let mut visited = vec![vec![false; self.width]; self.height];
+        let mut out = Vec::new();
+        let mut candidates = vec![start];
+
+        while let Some((x, y)) = candidates.pop() {
+            if visited[y][x] || !passable(&self[(x, y)]) {
+                continue;
+            }
+            visited[y][x] = true;
+            out.push((x, y));
+            candidates.extend(self.neighbors((x, y)).filter(|&(i, j)| !visited[j][i]));
+        }
+
+        out
+    }
+
+    /// Labels the orthogonally connected components of sites fulfilling the `predicate`.
+    pub fn components(&self, predicate: impl Fn(&T) -> bool) -> Labels {
+        let mut labels = Map {
+            width: self.width,
+            height: self.height,
+            values: vec![vec![None; self.width]; self.height],
+        };
+        let mut regions = Vec::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if labels[(x, y)].is_some() || !predicate(&self[(x, y)]) {
+                    continue;
+                }
+
+                // flood fill, the labels double as the visited sites of all components
+                let label = regions.len();
+                let mut sites = Vec::new();
+                let mut candidates = vec![(x, y)];
+                labels[(x, y)] = Some(label);
+                while let Some(site) = candidates.pop() {
+                    sites.push(site);
+                    for n in self.neighbors(site) {
+                        if labels[n].is_none() && predicate(&self[n]) {
+                            labels[n] = Some(label);
+                            candidates.push(n);
+                        }
+                    }
+                }
+
+                let perimeter = sites.iter()
+                    .map(|&site| 4 - self.neighbors(site).filter(|&n| labels[n] == Some(label)).count())
+                    .sum();
+                let bounding_box = (
+                    (sites.iter().map(|s| s.0).min().unwrap(), sites.iter().map(|s| s.1).min().unwrap()),
+                    (sites.iter().map(|s| s.0).max().unwrap(), sites.iter().map(|s| s.1).max().unwrap()),
+                );
+
+                regions.push(Region {
+                    size: sites.len(),
+                    perimeter,
+                    bounding_box,
+                });
+            }
+        }
+
+        Labels {
+            labels,
+            regions,
+        }
+    }
+}
+
+/// Tests whether a site is enclosed by a closed loop of orthogonally
+/// adjacent sites, which are given in order along the loop.
+///
+/// Sites on the loop are not enclosed.
+pub fn is_enclosed(closed_loop: &[Coord], site: Coord) -> bool {
+    let on_loop: FxHashSet<Coord> = closed_loop.iter().cloned().collect();
+    if on_loop.contains(&site) {
+        return false;
+    }
+
+    // cast a ray to the left and count how often it crosses the loop,
+    // crossing a loop site only counts if the loop continues to the north
+    let north = north_connected(closed_loop);
+    let (x, y) = site;
+    (0..x).filter(|&i| north.contains(&(i, y))).count() % 2 == 1
+}
+
+/// Counts the sites enclosed by a closed loop of orthogonally adjacent
+/// sites, which are given in order along the loop.
+pub fn count_enclosed(closed_loop: &[Coord]) -> usize {
+    let on_loop: FxHashSet<Coord> = closed_loop.iter().cloned().collect();
+    let north = north_connected(closed_loop);
+
+    let Some(x_max) = closed_loop.iter().map(|c| c.0).max() else {
+        return 0
+    };
+    let (y_min, y_max) = closed_loop.iter()
+        .fold((usize::MAX, 0), |(lo, hi), c| (lo.min(c.1), hi.max(c.1)));
+
+    let mut enclosed = 0;
+    for y in y_min..=y_max {
+        let mut inside = false;
+        for x in 0..=x_max {
+            if north.contains(&(x, y)) {
+                inside = !inside;
+            } else if inside && !on_loop.contains(&(x, y)) {
+                enclosed += 1;
+            }
+        }
+    }
+
+    enclosed
+}
+
+/// Sites of the loop which are connected to the site above them.
+fn north_connected(closed_loop: &[Coord]) -> FxHashSet<Coord> {
+    closed_loop.iter()
+        .zip(closed_loop.iter().cycle().skip(1))
+        .filter(|(a, b)| a.0 == b.0)
+        .map(|(a, b)| if a.1 > b.1 {*a} else {*b})
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labeling() {
+        let map: Map<char> = r"
+            AAB.
+            .AB.
+            C..C
+        ".parse().unwrap();
+
+        let labels = map.components(|&c| c != '.');
+        assert_eq!(labels.len(), 3);
+        assert_eq!(labels.sizes().collect::<Vec<_>>(), vec![5, 1, 1]);
+        assert_eq!(labels.regions[0].perimeter, 10);
+        assert_eq!(labels.regions[0].bounding_box, ((0, 0), (2, 1)));
+        assert_eq!(labels.sites(2).collect::<Vec<_>>(), vec![(3, 2)]);
+
+        let labels = map.components(|&c| c == 'A');
+        assert_eq!(labels.sizes().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(labels.labels[(2, 0)], None);
+
+        assert_eq!(map.flood_fill((0, 0), |&c| c != '.').len(), 5);
+        assert_eq!(map.flood_fill((0, 1), |&c| c != '.').len(), 0);
+    }
+
+    #[test]
+    fn many_components() {
+        // every component is a single site, which is only fast, if the
+        // components do not each scan the whole map
+        let n = 300;
+        let checkerboard = Map {
+            width: n,
+            height: n,
+            values: (0..n).map(|y| (0..n).map(|x| (x + y) % 2 == 0).collect()).collect(),
+        };
+        let labels = checkerboard.components(|&b| b);
+        assert_eq!(labels.len(), n * n / 2);
+        assert!(labels.regions.iter().all(|r| r.size == 1 && r.perimeter == 4));
+    }
+
+    #[test]
+    fn enclosed() {
+        // a loop with a notch
+        //   #####
+        //   #...#
+        //   #.###
+        //   #.#
+        //   ###
+        let closed_loop = vec![
+            (0, 0), (1, 0), (2, 0), (3, 0), (4, 0),
+            (4, 1), (4, 2), (3, 2), (2, 2), (2, 3),
+            (2, 4), (1, 4), (0, 4), (0, 3), (0, 2), (0, 1),
+        ];
+
+        assert_eq!(count_enclosed(&closed_loop), 5);
+        assert!(is_enclosed(&closed_loop, (1, 3)));
+        assert!(is_enclosed(&closed_loop, (3, 1)));
+        assert!(!is_enclosed(&closed_loop, (3, 3)));
+        assert!(!is_enclosed(&closed_loop, (2, 2)));
+    }
+}
@@ -0,0 +1,210 @@
+use std::{hash::Hash, ops::{Mul, Neg}};
+
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+
+use super::{Point, Scalar};
+
+/// One of the 24 rotations which map a cube onto itself.
+///
+/// Represented as a signed permutation of the axes with determinant +1,
+/// i.e., the `i`-th coordinate of the image is the `axes[i]`-th coordinate
+/// of the original, negated if `negate[i]` is set.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Rotation3 {
+    axes: [usize; 3],
+    negate: [bool; 3],
+}
+
+impl Rotation3 {
+    pub fn identity() -> Rotation3 {
+        Rotation3 {
+            axes: [0, 1, 2],
+            negate: [false; 3],
+        }
+    }
+
+    /// All 24 elements of the rotation group of the cube, starting with the identity.
+    pub fn all() -> impl Iterator<Item=Rotation3> {
+        (0..3).permutations(3)
+            .cartesian_product(0..8)
+            .map(|(axes, signs)| Rotation3 {
+                axes: [axes[0], axes[1], axes[2]],
+                negate: [signs & 1 != 0, signs & 2 != 0, signs & 4 != 0],
+            })
+            .filter(|r| r.determinant() == 1)
+    }
+
+    fn determinant(&self) -> isize {
+        // the sign of a permutation of three elements is given by its number of inversions
+        let inversions = (0..3).tuple_combinations()
+            .filter(|&(i, j)| self.axes[i] > self.axes[j])
+            .count();
+        let negations = self.negate.iter().filter(|&&n| n).count();
+
+        if (inversions + negations) % 2 == 0 {1} else {-1}
+    }
+
+    pub fn inverse(&self) -> Rotation3 {
+        let mut inverse = Rotation3::identity();
+        for i in 0..3 {
+            inverse.axes[self.axes[i]] = i;
+            inverse.negate[self.axes[i]] = self.negate[i];
+        }
+        inverse
+    }
+
+    /// Rotation which first applies `other` and then `self`.
+    pub fn compose(&self, other: &Rotation3) -> Rotation3 {
+        let mut composition = Rotation3::identity();
+        for i in 0..3 {
+            composition.axes[i] = other.axes[self.axes[i]];
+            composition.negate[i] = self.negate[i] ^ other.negate[self.axes[i]];
+        }
+        composition
+    }
+
+    pub fn apply<T>(&self, point: &Point<T, 3>) -> Point<T, 3>
+        where T: Scalar + Neg<Output = T>
+    {
+        Point::from_array(std::array::from_fn(|i| {
+            let c = point[self.axes[i]];
+            if self.negate[i] {-c} else {c}
+        }))
+    }
+
+    pub fn apply_all<T>(&self, points: &[Point<T, 3>]) -> Vec<Point<T, 3>>
+        where T: Scalar + Neg<Output = T>
+    {
+        points.iter().map(|p| self.apply(p)).collect()
+    }
+}
+
+impl Mul for Rotation3 {
+    type Output = Rotation3;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.compose(&rhs)
+    }
+}
+
+/// A rotation followed by a translation, i.e., a proper rigid motion on the lattice.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct RigidTransform3<T> {
+    pub rotation: Rotation3,
+    pub translation: Point<T, 3>,
+}
+
+impl<T: Scalar + Neg<Output = T>> RigidTransform3<T> {
+    pub fn identity() -> RigidTransform3<T> {
+        RigidTransform3 {
+            rotation: Rotation3::identity(),
+            translation: Point::zero(),
+        }
+    }
+
+    pub fn apply(&self, point: &Point<T, 3>) -> Point<T, 3> {
+        self.rotation.apply(point) + self.translation
+    }
+
+    pub fn apply_all(&self, points: &[Point<T, 3>]) -> Vec<Point<T, 3>> {
+        points.iter().map(|p| self.apply(p)).collect()
+    }
+
+    pub fn inverse(&self) -> RigidTransform3<T> {
+        let rotation = self.rotation.inverse();
+        RigidTransform3 {
+            rotation,
+            translation: -rotation.apply(&self.translation),
+        }
+    }
+
+    /// Transform which first applies `other` and then `self`.
+    pub fn compose(&self, other: &RigidTransform3<T>) -> RigidTransform3<T> {
+        RigidTransform3 {
+            rotation: self.rotation.compose(&other.rotation),
+            translation: self.apply(&other.translation),
+        }
+    }
+}
+
+/// Finds a rigid transform which maps at least `min_overlap` points of `b`
+/// onto points of `a`, if there is one.
+///
+/// For every rotation, the difference vectors between all pairs of points are
+/// counted. A translation which occurs `min_overlap` times aligns the clouds.
+pub fn register<T>(a: &[Point<T, 3>], b: &[Point<T, 3>], min_overlap: usize) -> Option<RigidTransform3<T>>
+    where T: Scalar + Neg<Output = T> + Hash + Eq
+{
+    Rotation3::all().find_map(|rotation| {
+        let rotated = rotation.apply_all(b);
+        let mut counts: FxHashMap<Point<T, 3>, usize> = FxHashMap::default();
+        for p in a {
+            for q in &rotated {
+                let count = counts.entry(*p - *q).or_insert(0);
+                *count += 1;
+                if *count >= min_overlap {
+                    return Some(RigidTransform3 {
+                        rotation,
+                        translation: *p - *q,
+                    })
+                }
+            }
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::utils::Point3;
+
+    use super::*;
+
+    #[test]
+    fn group() {
+        let all: Vec<Rotation3> = Rotation3::all().collect();
+        assert_eq!(all.len(), 24);
+        assert_eq!(all[0], Rotation3::identity());
+
+        let p = Point3::new(1, 2, 3);
+        let images: HashSet<Point3> = all.iter().map(|r| r.apply(&p)).collect();
+        assert_eq!(images.len(), 24);
+
+        for r in &all {
+            assert_eq!(r.compose(&r.inverse()), Rotation3::identity());
+            assert_eq!(r.inverse().apply(&r.apply(&p)), p);
+            for s in &all {
+                let rs = *r * *s;
+                assert!(all.contains(&rs));
+                assert_eq!(rs.apply(&p), r.apply(&s.apply(&p)));
+            }
+        }
+    }
+
+    #[test]
+    fn registration() {
+        let a = vec![
+            Point3::new(0, 0, 0),
+            Point3::new(1, 5, 2),
+            Point3::new(-3, 2, 7),
+            Point3::new(4, -1, 1),
+            Point3::new(9, 9, -9),
+        ];
+        let secret = RigidTransform3 {
+            rotation: Rotation3::all().nth(17).unwrap(),
+            translation: Point3::new(10, -20, 30),
+        };
+        // b is a seen from a different position and orientation, and misses one point
+        let b = secret.inverse().apply_all(&a[1..]);
+
+        let transform = register(&a, &b, 4).expect("no alignment found");
+        assert_eq!(transform, secret);
+        assert_eq!(transform.apply_all(&b), a[1..]);
+        assert_eq!(transform.compose(&transform.inverse()), RigidTransform3::identity());
+
+        assert!(register(&a, &b, 5).is_none());
+    }
+}
@@ -0,0 +1,158 @@
+use std::hash::Hash;
+
+use rustc_hash::FxHashMap;
+
+/// Counters describing the effort of a search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// states whose successors were generated
+    pub explored: usize,
+    /// states which were answered from the cache or were already known
+    pub cache_hits: usize,
+    /// states which were discarded because of their lower bound
+    pub pruned: usize,
+}
+
+/// A memoized recursive function of a state.
+///
+/// The cache lives as long as this value, such that independent problems
+/// do not share (or pollute) a global cache.
+///
+/// ```
+/// use aoc2021::utils::search::Memo;
+///
+/// let mut fibonacci = Memo::new(&|memo: &mut Memo<u64, u64>, &n: &u64| {
+///     if n < 2 {n} else {memo.get(n - 1) + memo.get(n - 2)}
+/// });
+/// assert_eq!(fibonacci.get(90), 2880067194370816120);
+/// assert_eq!(fibonacci.stats.explored, 91);
+/// ```
+pub struct Memo<'f, S, V> {
+    cache: FxHashMap<S, V>,
+    f: &'f dyn Fn(&mut Memo<'f, S, V>, &S) -> V,
+    pub stats: Stats,
+}
+
+impl<'f, S: Hash + Eq + Clone, V: Clone> Memo<'f, S, V> {
+    /// `f` calculates the value of a state and may use the memo to get the values of other states.
+    pub fn new(f: &'f dyn Fn(&mut Memo<'f, S, V>, &S) -> V) -> Memo<'f, S, V> {
+        Memo {
+            cache: FxHashMap::default(),
+            f,
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn get(&mut self, state: S) -> V {
+        if let Some(value) = self.cache.get(&state) {
+            self.stats.cache_hits += 1;
+            return value.clone()
+        }
+
+        self.stats.explored += 1;
+        let value = (self.f)(self, &state);
+        self.cache.insert(state, value.clone());
+        value
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+/// Depth first branch and bound for the cheapest way from `start` to a state
+/// fulfilling `goal`.
+///
+/// `successors` yields the following states together with the cost of the
+/// transition to them, `lower_bound` has to be admissible, i.e., never
+/// overestimate the remaining cost to a goal. Cheap looking successors are
+/// explored first, such that a good upper bound is found early.
+///
+/// Returns the minimal cost, or `None` if no goal is reachable, and the
+/// counters of the search.
+pub fn branch_and_bound<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut lower_bound: impl FnMut(&S) -> usize,
+    mut goal: impl FnMut(&S) -> bool
+) -> (Option<usize>, Stats)
+    where S: Hash + Eq + Clone, I: IntoIterator<Item=(S, usize)>
+{
+    let mut best: Option<usize> = None;
+    let mut stats = Stats::default();
+    // cheapest known cost to reach a state
+    let mut known: FxHashMap<S, usize> = FxHashMap::default();
+    let mut stack = vec![(start, 0)];
+
+    while let Some((state, cost)) = stack.pop() {
+        if goal(&state) {
+            best = Some(best.map_or(cost, |b| b.min(cost)));
+            continue;
+        }
+
+        match known.get(&state) {
+            Some(&k) if k <= cost => {
+                stats.cache_hits += 1;
+                continue;
+            },
+            _ => {},
+        }
+
+        if best.is_some_and(|b| cost + lower_bound(&state) >= b) {
+            stats.pruned += 1;
+            continue;
+        }
+
+        stats.explored += 1;
+        let mut next: Vec<(usize, S, usize)> = successors(&state).into_iter()
+            .map(|(s, c)| (cost + c + lower_bound(&s), s, cost + c))
+            .collect();
+        // the most promising successor has to be on top of the stack
+        next.sort_by_key(|&(estimate, _, _)| std::cmp::Reverse(estimate));
+        stack.extend(next.into_iter().map(|(_, s, c)| (s, c)));
+
+        known.insert(state, cost);
+    }
+
+    (best, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memo() {
+        // number of lattice paths through a grid
+        let mut paths = Memo::new(&|memo: &mut Memo<(u64, u64), u64>, &(x, y): &(u64, u64)| {
+            if x == 0 || y == 0 {
+                1
+            } else {
+                memo.get((x - 1, y)) + memo.get((x, y - 1))
+            }
+        });
+        assert_eq!(paths.get((16, 16)), 601080390);
+        assert_eq!(paths.stats.explored, 17 * 17 - 1);
+        assert!(paths.stats.cache_hits > 0);
+
+        // a new memo starts with an empty cache
+        let mut other = Memo::new(&|_: &mut Memo<(u64, u64), u64>, _: &(u64, u64)| 0);
+        assert_eq!(other.get((16, 16)), 0);
+    }
+
+    #[test]
+    fn bound() {
+        // reach 100 from 1 by adding one (cost 1) or doubling (cost 3)
+        let successors = |&n: &usize| [(n + 1, 1), (2 * n, 3)].into_iter().filter(|&(m, _)| m <= 100);
+        let (best, stats) = branch_and_bound(1usize, successors, |_| 0, |&n| n == 100);
+        assert_eq!(best, Some(18));
+
+        // halving the remaining distance costs at least 3
+        let informed = |&n: &usize| if n == 100 {0} else if 2 * n > 100 {1} else {3};
+        let (best, informed_stats) = branch_and_bound(1usize, successors, informed, |&n| n == 100);
+        assert_eq!(best, Some(18));
+        assert!(stats.pruned > 0 && informed_stats.pruned > 0);
+
+        assert_eq!(branch_and_bound(1usize, successors, |_| 0, |&n| n == 101).0, None);
+    }
+}
@@ -1,8 +1,9 @@
-use std::ops::Index;
 use std::hash::Hash;
 
 use priority_queue::DoublePriorityQueue;
-use rustc_hash::{FxHashSet, FxHashMap};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::paths::{all_paths, SimplePaths};
 
 pub trait Neighborful<C> {
     fn neighbors(&self, coordinate: C) -> impl Iterator<Item=C> + '_;
@@ -10,47 +11,599 @@ pub trait Neighborful<C> {
     fn distance(c1: C, c2: C) -> usize;
 }
 
-pub fn astar<G, C>(graph: &G, start: C, end: C) -> Vec<C>
-    where G: Neighborful<C> + Index<C, Output = u8>, C: Hash + Eq + Copy
+/// State of a search right after a node was expanded, see `Observer`.
+pub struct Progress<'a, N: Hash + Eq> {
+    /// number of expanded nodes so far, including `current`
+    pub expansions: usize,
+    pub current: &'a N,
+    frontier: Frontier<'a, N>,
+}
+
+/// The bookkeeping of the different searches, from which `Progress` derives
+/// the open and closed nodes.
+enum Frontier<'a, N: Hash + Eq> {
+    /// priority queue and the costs of all discovered nodes
+    Queue(&'a DoublePriorityQueue<N, usize>, &'a FxHashMap<N, usize>),
+    /// all discovered nodes in order, of which the first ones are expanded
+    Fifo(&'a [N], usize),
+}
+
+impl<N: Hash + Eq> Progress<'_, N> {
+    /// The nodes which are discovered, but not yet expanded.
+    pub fn open(&self) -> impl Iterator<Item=&N> {
+        let open: Box<dyn Iterator<Item=&N>> = match self.frontier {
+            Frontier::Queue(open_list, _) => Box::new(open_list.iter().map(|(n, _)| n)),
+            Frontier::Fifo(discovered, expanded) => Box::new(discovered[expanded..].iter()),
+        };
+        open
+    }
+
+    /// The nodes which are already expanded, including `current`.
+    pub fn closed(&self) -> impl Iterator<Item=&N> {
+        let closed: Box<dyn Iterator<Item=&N>> = match self.frontier {
+            Frontier::Queue(open_list, g) => Box::new(g.keys().filter(|n| open_list.get(n).is_none())),
+            Frontier::Fifo(discovered, expanded) => Box::new(discovered[..expanded].iter()),
+        };
+        closed
+    }
+}
+
+/// Hooks into a search, e.g., to visualize which nodes it explores.
+///
+/// `()` is an observer which ignores everything.
+pub trait Observer<N: Hash + Eq> {
+    fn expanded(&mut self, _progress: &Progress<N>) {}
+
+    /// Called once at the end of the search with the found path, `None` if
+    /// there is none or the search does not look for one.
+    fn finished(&mut self, _path: Option<&[N]>) {}
+}
+
+impl<N: Hash + Eq> Observer<N> for () {}
+
+/// A* search from `start` to the first node fulfilling `goal`.
+///
+/// `successors` yields the neighbors of a node together with the cost of the
+/// edge to them, `heuristic` is a lower bound on the remaining cost to a goal.
+/// With a heuristic which is always zero, this is Dijkstra's algorithm.
+///
+/// Returns the total cost and the path from `start` to the goal (both included),
+/// or `None` if no goal is reachable.
+pub fn astar<N, S, I>(
+    start: N,
+    successors: S,
+    heuristic: impl FnMut(&N) -> usize,
+    goal: impl FnMut(&N) -> bool
+) -> Option<(usize, Vec<N>)>
+    where N: Hash + Eq + Clone, S: FnMut(&N) -> I, I: IntoIterator<Item=(N, usize)>
+{
+    astar_observed(start, successors, heuristic, goal, &mut ())
+}
+
+/// `astar`, which reports every expansion and the result to the `observer`.
+pub fn astar_observed<N, S, I>(
+    start: N,
+    mut successors: S,
+    mut heuristic: impl FnMut(&N) -> usize,
+    mut goal: impl FnMut(&N) -> bool,
+    observer: &mut impl Observer<N>
+) -> Option<(usize, Vec<N>)>
+    where N: Hash + Eq + Clone, S: FnMut(&N) -> I, I: IntoIterator<Item=(N, usize)>
 {
     // https://de.wikipedia.org/wiki/A*-Algorithmus
     let mut open_list = DoublePriorityQueue::new();
-    let mut closed_list = FxHashSet::default();
-    let mut g: FxHashMap<C, usize> = FxHashMap::default();
-    let mut predecessor: FxHashMap<C, C> = FxHashMap::default();
+    let mut g: FxHashMap<N, usize> = FxHashMap::default();
+    let mut predecessor: FxHashMap<N, N> = FxHashMap::default();
+    let mut expansions = 0;
 
-    open_list.push(start, 0);
+    open_list.push(start.clone(), heuristic(&start));
     g.insert(start, 0);
     while let Some((current, _weight)) = open_list.pop_min() {
-        if current == end {
+        if goal(&current) {
             // path complete
-            let mut tmp = end;
-            let mut path = vec![end];
-            while tmp != start {
-                let next = predecessor[&tmp];
-                path.push(next);
-                tmp = next;
+            let cost = g[&current];
+            let mut path = vec![current];
+            while let Some(next) = predecessor.get(path.last().unwrap()) {
+                path.push(next.clone());
             }
-            return path
+            path.reverse();
+            observer.finished(Some(&path));
+            return Some((cost, path))
         }
-        closed_list.insert(current);
 
-        for n in graph.neighbors(current) {
-            if closed_list.contains(&n) {
+        let g_current = g[&current];
+        for (n, cost) in successors(&current) {
+            let tentative = g_current + cost;
+            // a node is reopened if we find a cheaper path to it, such that
+            // an admissible but inconsistent heuristic still yields the optimum
+            if g.get(&n).is_some_and(|&known| tentative >= known) {
                 continue;
             }
-            let tentative = g[&current] + graph[n] as usize;
-            if open_list.get(&n).is_some() && tentative >= g[&n] {
-                continue;
+            let f = tentative + heuristic(&n);
+            predecessor.insert(n.clone(), current.clone());
+            g.insert(n.clone(), tentative);
+            open_list.push(n, f);
+        }
+
+        expansions += 1;
+        observer.expanded(&Progress {
+            expansions,
+            current: &current,
+            frontier: Frontier::Queue(&open_list, &g),
+        });
+    }
+
+    observer.finished(None);
+    None
+}
+
+/// Dijkstra's algorithm from `start` to the first node fulfilling `goal`.
+pub fn dijkstra<N, S, I>(start: N, successors: S, goal: impl FnMut(&N) -> bool) -> Option<(usize, Vec<N>)>
+    where N: Hash + Eq + Clone, S: FnMut(&N) -> I, I: IntoIterator<Item=(N, usize)>
+{
+    dijkstra_observed(start, successors, goal, &mut ())
+}
+
+/// `dijkstra`, which reports every expansion and the result to the `observer`.
+pub fn dijkstra_observed<N, S, I>(
+    start: N,
+    successors: S,
+    goal: impl FnMut(&N) -> bool,
+    observer: &mut impl Observer<N>
+) -> Option<(usize, Vec<N>)>
+    where N: Hash + Eq + Clone, S: FnMut(&N) -> I, I: IntoIterator<Item=(N, usize)>
+{
+    astar_observed(start, successors, |_| 0, goal, observer)
+}
+
+/// All cheapest paths from a start to the goals, see `astar_all`.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<N: Hash + Eq> {
+    pub cost: usize,
+    pub start: N,
+    /// all goals, which can be reached at `cost`
+    pub goals: Vec<N>,
+    /// cost of the cheapest known path to every discovered node
+    g: FxHashMap<N, usize>,
+    /// all predecessors on cheapest paths
+    predecessors: FxHashMap<N, Vec<N>>,
+}
+
+impl<N: Hash + Eq + Clone> ShortestPaths<N> {
+    /// The nodes which lie on any cheapest path.
+    pub fn nodes(&self) -> FxHashSet<N> {
+        let mut nodes: FxHashSet<N> = self.goals.iter().cloned().collect();
+        let mut stack = self.goals.clone();
+        while let Some(n) = stack.pop() {
+            for p in self.predecessors.get(&n).into_iter().flatten() {
+                if nodes.insert(p.clone()) {
+                    stack.push(p.clone());
+                }
             }
-            predecessor.insert(n, current);
-            g.insert(n, tentative);
-            // lower bound on the remaining cost
-            let h = G::distance(end, n);
-            let f = tentative + h;
+        }
+        nodes
+    }
+
+    /// Number of distinct cheapest paths.
+    pub fn count(&self) -> usize {
+        // predecessors are cheaper than their successors for positive edge costs,
+        // such that the nodes ordered by cost are topologically sorted
+        let mut nodes: Vec<N> = self.nodes().into_iter().collect();
+        nodes.sort_by_key(|n| self.g[n]);
+
+        let mut count: FxHashMap<N, usize> = FxHashMap::default();
+        for n in nodes {
+            let c = if n == self.start {
+                1
+            } else {
+                self.predecessors[&n].iter().map(|p| count[p]).sum()
+            };
+            count.insert(n, c);
+        }
+
+        self.goals.iter().map(|goal| count[goal]).sum()
+    }
+
+    /// One of the cheapest paths, from the start to a goal.
+    pub fn path(&self) -> Vec<N> {
+        let mut path = vec![self.goals[0].clone()];
+        while let Some(next) = self.predecessors.get(path.last().unwrap()).and_then(|p| p.first()) {
+            path.push(next.clone());
+        }
+        path.reverse();
+        path
+    }
+
+    /// All cheapest paths, each from the start to a goal.
+    pub fn paths(&self) -> impl Iterator<Item=Vec<N>> + '_ {
+        self.goals.iter().flat_map(move |goal| -> Box<dyn Iterator<Item=Vec<N>>> {
+            if *goal == self.start {
+                return Box::new(std::iter::once(vec![goal.clone()]))
+            }
+            // walk backwards along the predecessors
+            let backwards = |n: &N| self.predecessors.get(n).cloned().unwrap_or_default();
+            Box::new(all_paths(goal.clone(), self.start.clone(), backwards, SimplePaths)
+                .map(|mut path| {
+                    path.reverse();
+                    path
+                }))
+        })
+    }
+}
+
+/// A* search, which keeps all cheapest paths to all goals of minimal cost.
+///
+/// In contrast to `astar`, the edge costs have to be positive and the
+/// heuristic has to be consistent, i.e., it does not decrease by more
+/// than the cost along any edge.
+pub fn astar_all<N, S, I>(
+    start: N,
+    successors: S,
+    heuristic: impl FnMut(&N) -> usize,
+    goal: impl FnMut(&N) -> bool
+) -> Option<ShortestPaths<N>>
+    where N: Hash + Eq + Clone, S: FnMut(&N) -> I, I: IntoIterator<Item=(N, usize)>
+{
+    astar_all_observed(start, successors, heuristic, goal, &mut ())
+}
+
+/// `astar_all`, which reports every expansion and one of the cheapest paths
+/// to the `observer`.
+pub fn astar_all_observed<N, S, I>(
+    start: N,
+    mut successors: S,
+    mut heuristic: impl FnMut(&N) -> usize,
+    mut goal: impl FnMut(&N) -> bool,
+    observer: &mut impl Observer<N>
+) -> Option<ShortestPaths<N>>
+    where N: Hash + Eq + Clone, S: FnMut(&N) -> I, I: IntoIterator<Item=(N, usize)>
+{
+    let mut open_list = DoublePriorityQueue::new();
+    let mut g: FxHashMap<N, usize> = FxHashMap::default();
+    let mut predecessors: FxHashMap<N, Vec<N>> = FxHashMap::default();
+    let mut best: Option<usize> = None;
+    let mut goals = Vec::new();
+    let mut expansions = 0;
+
+    open_list.push(start.clone(), heuristic(&start));
+    g.insert(start.clone(), 0);
+    while let Some((current, f)) = open_list.pop_min() {
+        // every node on a cheapest path has an estimate not above the cost
+        if best.is_some_and(|b| f > b) {
+            break;
+        }
+        if goal(&current) {
+            best = Some(g[&current]);
+            goals.push(current);
+            continue;
+        }
+
+        let g_current = g[&current];
+        for (n, cost) in successors(&current) {
+            let tentative = g_current + cost;
+            match g.get(&n) {
+                Some(&known) if tentative > known => continue,
+                Some(&known) if tentative == known => {
+                    predecessors.entry(n).or_default().push(current.clone());
+                    continue;
+                },
+                _ => {},
+            }
+            let f = tentative + heuristic(&n);
+            predecessors.insert(n.clone(), vec![current.clone()]);
+            g.insert(n.clone(), tentative);
             open_list.push(n, f);
         }
+
+        expansions += 1;
+        observer.expanded(&Progress {
+            expansions,
+            current: &current,
+            frontier: Frontier::Queue(&open_list, &g),
+        });
+    }
+
+    let Some(cost) = best else {
+        observer.finished(None);
+        return None
+    };
+    let paths = ShortestPaths {
+        cost,
+        start,
+        goals,
+        g,
+        predecessors,
+    };
+    observer.finished(Some(&paths.path()));
+    Some(paths)
+}
+
+/// Dijkstra's algorithm, which keeps all cheapest paths to all goals of minimal cost.
+pub fn dijkstra_all<N, S, I>(start: N, successors: S, goal: impl FnMut(&N) -> bool) -> Option<ShortestPaths<N>>
+    where N: Hash + Eq + Clone, S: FnMut(&N) -> I, I: IntoIterator<Item=(N, usize)>
+{
+    astar_all_observed(start, successors, |_| 0, goal, &mut ())
+}
+
+/// Breadth first search from one or more `starts`.
+///
+/// Returns the number of steps from the nearest start to every reachable node.
+pub fn bfs_distances<C, I>(starts: impl IntoIterator<Item=C>, successors: impl FnMut(&C) -> I) -> FxHashMap<C, usize>
+    where C: Hash + Eq + Clone, I: IntoIterator<Item=C>
+{
+    bfs_distances_observed(starts, successors, &mut ())
+}
+
+/// `bfs_distances`, which reports every expansion to the `observer`.
+pub fn bfs_distances_observed<C, I>(
+    starts: impl IntoIterator<Item=C>,
+    mut successors: impl FnMut(&C) -> I,
+    observer: &mut impl Observer<C>
+) -> FxHashMap<C, usize>
+    where C: Hash + Eq + Clone, I: IntoIterator<Item=C>
+{
+    let mut distances: FxHashMap<C, usize> = FxHashMap::default();
+    // all discovered nodes in order, the queue is the part after `expanded`
+    let mut discovered = Vec::new();
+    for start in starts {
+        if !distances.contains_key(&start) {
+            distances.insert(start.clone(), 0);
+            discovered.push(start);
+        }
     }
 
-    panic!("no path found")
-}
\ No newline at end of file
+    let mut expanded = 0;
+    while expanded < discovered.len() {
+        let current = discovered[expanded].clone();
+        let d = distances[&current] + 1;
+        for n in successors(&current) {
+            if !distances.contains_key(&n) {
+                distances.insert(n.clone(), d);
+                discovered.push(n);
+            }
+        }
+
+        expanded += 1;
+        observer.expanded(&Progress {
+            expansions: expanded,
+            current: &current,
+            frontier: Frontier::Fifo(&discovered, expanded),
+        });
+    }
+
+    observer.finished(None);
+    distances
+}
+
+/// Breadth first search from `start` to the nearest node fulfilling `goal`.
+///
+/// Returns the path from `start` to the goal (both included), or `None` if
+/// no goal is reachable.
+pub fn bfs_path<C, I>(start: C, successors: impl FnMut(&C) -> I, goal: impl FnMut(&C) -> bool) -> Option<Vec<C>>
+    where C: Hash + Eq + Clone, I: IntoIterator<Item=C>
+{
+    bfs_path_observed(start, successors, goal, &mut ())
+}
+
+/// `bfs_path`, which reports every expansion and the result to the `observer`.
+pub fn bfs_path_observed<C, I>(
+    start: C,
+    mut successors: impl FnMut(&C) -> I,
+    mut goal: impl FnMut(&C) -> bool,
+    observer: &mut impl Observer<C>
+) -> Option<Vec<C>>
+    where C: Hash + Eq + Clone, I: IntoIterator<Item=C>
+{
+    let mut predecessor: FxHashMap<C, Option<C>> = FxHashMap::default();
+    // all discovered nodes in order, the queue is the part after `expanded`
+    let mut discovered = vec![start.clone()];
+    predecessor.insert(start, None);
+
+    let mut expanded = 0;
+    while expanded < discovered.len() {
+        let current = discovered[expanded].clone();
+        if goal(&current) {
+            let mut path = vec![current];
+            while let Some(Some(next)) = predecessor.get(path.last().unwrap()) {
+                path.push(next.clone());
+            }
+            path.reverse();
+            observer.finished(Some(&path));
+            return Some(path)
+        }
+        for n in successors(&current) {
+            if !predecessor.contains_key(&n) {
+                predecessor.insert(n.clone(), Some(current.clone()));
+                discovered.push(n);
+            }
+        }
+
+        expanded += 1;
+        observer.expanded(&Progress {
+            expansions: expanded,
+            current: &current,
+            frontier: Frontier::Fifo(&discovered, expanded),
+        });
+    }
+
+    observer.finished(None);
+    None
+}
+
+/// Inverts a neighbor function on a finite set of `nodes`, i.e., the
+/// result maps every node to all nodes which have it as a successor.
+///
+/// Searching on the reversed graph finds the distances *to* a node.
+pub fn reversed<C, I>(nodes: impl IntoIterator<Item=C>, mut successors: impl FnMut(&C) -> I) -> FxHashMap<C, Vec<C>>
+    where C: Hash + Eq + Clone, I: IntoIterator<Item=C>
+{
+    let mut predecessors: FxHashMap<C, Vec<C>> = FxHashMap::default();
+    for node in nodes {
+        for n in successors(&node) {
+            predecessors.entry(n).or_default().push(node.clone());
+        }
+    }
+    predecessors
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::Map;
+
+    use super::*;
+
+    #[test]
+    fn grid() {
+        let map: Map<u8> = r"
+            191
+            151
+            111
+        ".parse().unwrap();
+        let graph = &map;
+
+        let end = (2, 0);
+        let (cost, path) = astar(
+            (0, 0),
+            |&c| graph.neighbors(c).map(|n| (n, map[n] as usize)),
+            |&c| <&Map<u8>>::distance(c, end),
+            |&c| c == end
+        ).unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(path, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 1), (2, 0)]);
+
+        let walls = dijkstra((0, 0), |&c| graph.neighbors(c).filter(|&n| map[n] < 5).map(|n| (n, 1)).collect::<Vec<_>>(), |&c| c == (1, 1));
+        assert_eq!(walls, None);
+    }
+
+    #[test]
+    fn state_space() {
+        // reach 100 from 1 by adding one (cost 1) or doubling (cost 3)
+        let (cost, path) = dijkstra(1usize, |&n| [(n + 1, 1), (2 * n, 3)].into_iter().filter(|&(m, _)| m <= 100), |&n| n == 100).unwrap();
+        assert_eq!(cost, 18);
+        assert_eq!(path.first(), Some(&1));
+        assert_eq!(path.last(), Some(&100));
+        assert!(path.windows(2).all(|w| w[1] == w[0] + 1 || w[1] == 2 * w[0]));
+    }
+
+    #[test]
+    fn all_shortest() {
+        let map: Map<char> = r"
+            ....
+            .#..
+            ....
+        ".parse().unwrap();
+        let graph = &map;
+        let free = |&c: &(usize, usize)| graph.neighbors(c).filter(|&n| map[n] == '.').map(|n| (n, 1));
+
+        let end = (3, 2);
+        let all = astar_all((0, 0), free, |&c| <&Map<char>>::distance(c, end), |&c| c == end).unwrap();
+        assert_eq!(all.cost, 5);
+        // around the wall on top (3 ways) or on the left (1 way)
+        assert_eq!(all.count(), 4);
+        assert_eq!(all.nodes().len(), 11);
+        assert!(!all.nodes().contains(&(1, 1)));
+
+        let path = all.path();
+        assert_eq!(path.len(), 6);
+        assert_eq!((path[0], path[5]), ((0, 0), end));
+        let mut paths: Vec<Vec<(usize, usize)>> = all.paths().collect();
+        assert_eq!(paths.len(), 4);
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), all.count());
+        assert!(paths.iter().all(|p| p[0] == (0, 0) && p[5] == end));
+
+        // both cells of the last column are goals at the same distance
+        let all = dijkstra_all((0, 1), free, |&c| c.0 == 3 && c.1 != 1).unwrap();
+        assert_eq!(all.cost, 4);
+        assert_eq!(all.goals.len(), 2);
+        assert_eq!(all.count(), 2);
+
+        assert!(dijkstra_all((0, 0), free, |&c| c == (1, 1)).is_none());
+    }
+
+    #[test]
+    fn bfs() {
+        let map: Map<char> = r"
+            ..#.
+            .##.
+            ....
+        ".parse().unwrap();
+        let graph = &map;
+        let free = |&c: &(usize, usize)| graph.neighbors(c).filter(|&n| map[n] == '.');
+
+        let distances = bfs_distances([(0, 0)], free);
+        assert_eq!(distances[&(3, 0)], 7);
+        assert_eq!(distances.len(), 9);
+
+        let distances = bfs_distances([(0, 0), (3, 0)], free);
+        assert_eq!(distances[&(3, 2)], 2);
+        assert_eq!(distances[&(1, 2)], 3);
+
+        let path = bfs_path((0, 0), free, |&c| c == (3, 1)).unwrap();
+        assert_eq!(path.len(), 7);
+        assert_eq!(path[0], (0, 0));
+        assert_eq!(path[6], (3, 1));
+        assert_eq!(bfs_path((0, 0), free, |&c| c == (2, 0)), None);
+
+        // one way from left to right
+        let successors = |&n: &usize| if n < 4 {vec![n + 1]} else {vec![]};
+        let predecessors = reversed(0..5, successors);
+        let to_end = bfs_distances([4], |n| predecessors.get(n).cloned().unwrap_or_default());
+        assert_eq!(to_end[&0], 4);
+        assert_eq!(bfs_distances([4], successors).len(), 1);
+    }
+
+    /// Checks that the open and closed nodes partition the discovered ones.
+    #[derive(Default)]
+    struct Recorder {
+        expansions: usize,
+        closed: usize,
+        path: Option<Vec<(usize, usize)>>,
+    }
+
+    impl Observer<(usize, usize)> for Recorder {
+        fn expanded(&mut self, progress: &Progress<(usize, usize)>) {
+            let closed: Vec<_> = progress.closed().collect();
+            assert!(closed.contains(&progress.current));
+            assert!(progress.open().all(|n| !closed.contains(&n)));
+            self.expansions = progress.expansions;
+            self.closed = closed.len();
+        }
+
+        fn finished(&mut self, path: Option<&[(usize, usize)]>) {
+            self.path = path.map(|p| p.to_vec());
+        }
+    }
+
+    #[test]
+    fn observed() {
+        let map: Map<char> = r"
+            ....
+            .#..
+            ....
+        ".parse().unwrap();
+        let graph = &map;
+        let free = |&c: &(usize, usize)| graph.neighbors(c).filter(|&n| map[n] == '.');
+        let weighted = |c: &(usize, usize)| free(c).map(|n| (n, 1));
+
+        let mut recorder = Recorder::default();
+        bfs_distances_observed([(0, 0)], free, &mut recorder);
+        assert_eq!((recorder.expansions, recorder.closed, recorder.path), (11, 11, None));
+
+        let mut recorder = Recorder::default();
+        let path = bfs_path_observed((0, 0), free, |&c| c == (3, 0), &mut recorder);
+        assert_eq!(recorder.path, path);
+        assert_eq!(recorder.expansions, recorder.closed);
+
+        let mut recorder = Recorder::default();
+        let (_, path) = dijkstra_observed((0, 0), weighted, |&c| c == (3, 2), &mut recorder).unwrap();
+        assert_eq!(recorder.path, Some(path));
+        assert_eq!(recorder.expansions, recorder.closed);
+
+        let mut recorder = Recorder::default();
+        let all = astar_all_observed((0, 0), weighted, |_| 0, |&c| c == (3, 2), &mut recorder).unwrap();
+        assert_eq!(recorder.path, Some(all.path()));
+        assert_eq!(recorder.expansions, recorder.closed);
+    }
+}
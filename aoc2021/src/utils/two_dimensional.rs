@@ -7,7 +7,7 @@ use super::{AdventError, shortest_path::Neighborful};
 
 pub type Coord = (usize, usize);
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Map<T> {
     pub width: usize,
     pub height: usize,
@@ -1,59 +1,253 @@
-#[derive(Clone)]
-struct Node{
-    parent: Option<usize>,
-    size: usize,
-}
+use std::hash::Hash;
+
+use rustc_hash::FxHashMap;
 
+/// Disjoint sets of the elements `0..n`.
+#[derive(Debug, Clone)]
 pub struct UnionFind {
-    forest: Vec<Node>,
+    parent: Vec<usize>,
+    /// size of the cluster, only valid for roots
+    size: Vec<usize>,
+    num_components: usize,
 }
 
 impl UnionFind {
     pub fn new(n: usize) -> UnionFind {
-        UnionFind { forest: vec![Node {parent: None, size: 1}; n] }
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            num_components: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
     }
 
-    pub fn size(&mut self, i: usize) -> usize {
-        let root = self.find(i);
-        self.forest[root].size
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Adds a new element in its own cluster and returns it.
+    pub fn push(&mut self) -> usize {
+        let i = self.parent.len();
+        self.parent.push(i);
+        self.size.push(1);
+        self.num_components += 1;
+        i
     }
 
     // we need mut because of path compression
-    pub fn find(&mut self, i: usize) -> usize {
-        let root = match self.forest[i].parent {
-            Some(above) => self.find(above),
-            None => i,
-        };
+    pub fn find(&mut self, mut i: usize) -> usize {
+        // path halving: every node on the way points to its grandparent afterwards
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
 
-        // path compression
-        if self.forest[i].parent.is_some() {
-            self.forest[i].parent = Some(root)
-        };
+    /// Like `find`, but without path compression.
+    pub fn root(&self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            i = self.parent[i];
+        }
+        i
+    }
 
-        root
+    pub fn size(&self, i: usize) -> usize {
+        self.size[self.root(i)]
     }
 
-    pub fn union(&mut self, i: usize, j: usize) {
+    pub fn same(&mut self, i: usize, j: usize) -> bool {
+        self.find(i) == self.find(j)
+    }
+
+    /// Merges the clusters of `i` and `j` and returns whether they were different.
+    pub fn union(&mut self, i: usize, j: usize) -> bool {
         let root_i = self.find(i);
         let root_j = self.find(j);
         // we are already in the same cluster
         if root_i == root_j {
-            return
+            return false
         }
 
-        // weighted
-        let smaller;
-        let larger;
-        if self.forest[root_i].size > self.forest[root_j].size {
-            larger = root_i;
-            smaller = root_j;
+        // weighted, the smaller tree is attached to the larger one
+        let (smaller, larger) = if self.size[root_i] > self.size[root_j] {
+            (root_j, root_i)
         } else {
-            larger = root_j;
-            smaller = root_i;
+            (root_i, root_j)
+        };
+
+        self.parent[smaller] = larger;
+        self.size[larger] += self.size[smaller];
+        self.num_components -= 1;
+        true
+    }
+
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    /// The elements of every cluster, ordered by their smallest element.
+    pub fn components(&self) -> impl Iterator<Item=Vec<usize>> {
+        let mut position: FxHashMap<usize, usize> = FxHashMap::default();
+        let mut components: Vec<Vec<usize>> = Vec::with_capacity(self.num_components);
+        for i in 0..self.len() {
+            let root = self.root(i);
+            let n = *position.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[n].push(i);
+        }
+        components.into_iter()
+    }
+}
+
+/// Disjoint sets of arbitrary hashable labels, which are added on first use.
+#[derive(Debug, Clone)]
+pub struct LabeledUnionFind<L> {
+    ids: FxHashMap<L, usize>,
+    labels: Vec<L>,
+    clusters: UnionFind,
+}
+
+impl<L: Hash + Eq + Clone> Default for LabeledUnionFind<L> {
+    fn default() -> Self {
+        LabeledUnionFind::new()
+    }
+}
+
+impl<L: Hash + Eq + Clone> LabeledUnionFind<L> {
+    pub fn new() -> LabeledUnionFind<L> {
+        LabeledUnionFind {
+            ids: FxHashMap::default(),
+            labels: Vec::new(),
+            clusters: UnionFind::new(0),
+        }
+    }
+
+    fn id(&mut self, label: L) -> usize {
+        if let Some(&id) = self.ids.get(&label) {
+            return id
         }
+        let id = self.clusters.push();
+        self.ids.insert(label.clone(), id);
+        self.labels.push(label);
+        id
+    }
+
+    /// Adds `label` in its own cluster, if it is not known yet.
+    pub fn insert(&mut self, label: L) {
+        self.id(label);
+    }
+
+    pub fn contains(&self, label: &L) -> bool {
+        self.ids.contains_key(label)
+    }
+
+    /// The representative of the cluster of `label`, `None` for unknown labels.
+    pub fn find(&mut self, label: &L) -> Option<&L> {
+        let &id = self.ids.get(label)?;
+        let root = self.clusters.find(id);
+        Some(&self.labels[root])
+    }
+
+    /// The size of the cluster of `label`, `None` for unknown labels.
+    pub fn size(&self, label: &L) -> Option<usize> {
+        self.ids.get(label).map(|&id| self.clusters.size(id))
+    }
+
+    /// Whether both labels are known and in the same cluster.
+    pub fn same(&mut self, a: &L, b: &L) -> bool {
+        match (self.ids.get(a), self.ids.get(b)) {
+            (Some(&a), Some(&b)) => self.clusters.same(a, b),
+            _ => false,
+        }
+    }
+
+    /// Merges the clusters of `a` and `b` and returns whether they were different.
+    pub fn union(&mut self, a: L, b: L) -> bool {
+        let a = self.id(a);
+        let b = self.id(b);
+        self.clusters.union(a, b)
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn num_components(&self) -> usize {
+        self.clusters.num_components()
+    }
+
+    /// The labels of every cluster, ordered by their first insertion.
+    pub fn components(&self) -> impl Iterator<Item=Vec<L>> + '_ {
+        self.clusters.components()
+            .map(|c| c.into_iter().map(|id| self.labels[id].clone()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clusters() {
+        let mut uf = UnionFind::new(6);
+        assert!(uf.union(0, 1));
+        assert!(uf.union(4, 1));
+        assert!(!uf.union(0, 4));
+        assert!(uf.union(2, 5));
+
+        assert!(uf.same(0, 4));
+        assert!(!uf.same(0, 5));
+        assert_eq!(uf.size(1), 3);
+        assert_eq!(uf.num_components(), 3);
+        assert_eq!(uf.components().collect::<Vec<_>>(), vec![vec![0, 1, 4], vec![2, 5], vec![3]]);
+    }
+
+    #[test]
+    fn long_chain() {
+        // a recursive find would overflow the stack here
+        let n = 1_000_000;
+        let mut uf = UnionFind::new(n);
+        for i in 1..n {
+            uf.parent[i - 1] = i;
+        }
+        uf.size[n - 1] = n;
+        uf.num_components = 1;
+        assert_eq!(uf.find(0), n - 1);
+        assert_eq!(uf.size(0), n);
+    }
+
+    #[test]
+    fn labeled() {
+        let mut uf = LabeledUnionFind::new();
+        uf.union("a", "b");
+        uf.union("c", "d");
+        uf.insert("e");
+        uf.union("b", "d");
+
+        assert!(uf.same(&"a", &"c"));
+        assert!(!uf.same(&"a", &"e"));
+        assert_eq!(uf.size(&"d"), Some(4));
+        let root = *uf.find(&"d").unwrap();
+        assert_eq!(uf.find(&"a"), Some(&root));
+        assert_eq!(uf.num_components(), 2);
 
-        self.forest[smaller].parent = Some(larger);
-        self.forest[larger].size += self.forest[smaller].size;
-        self.forest[smaller].size = 0;
+        // queries do not add unknown labels
+        assert_eq!(uf.find(&"x"), None);
+        assert_eq!(uf.size(&"x"), None);
+        assert!(!uf.same(&"x", &"x"));
+        assert!(!uf.contains(&"x"));
+        assert_eq!(uf.len(), 5);
+        assert_eq!(uf.num_components(), 2);
+        assert_eq!(uf.components().collect::<Vec<_>>(), vec![vec!["a", "b", "c", "d"], vec!["e"]]);
     }
 }
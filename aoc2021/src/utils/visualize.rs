@@ -0,0 +1,264 @@
+use std::{fs::File, io::{self, Write, BufWriter}, path::PathBuf};
+
+use colored::Colorize;
+
+use super::{Map, Coord, shortest_path::{Observer, Progress}};
+
+/// Role of a site in a search on a `Map`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Unvisited,
+    /// discovered, but not yet expanded
+    Open,
+    /// expanded
+    Closed,
+    /// expanded last
+    Current,
+    /// on the final path
+    Path,
+}
+
+impl Cell {
+    fn rgb(&self) -> [u8; 3] {
+        match self {
+            Cell::Unvisited => [40, 40, 40],
+            Cell::Open => [230, 200, 40],
+            Cell::Closed => [40, 90, 200],
+            Cell::Current => [220, 40, 40],
+            Cell::Path => [40, 200, 70],
+        }
+    }
+}
+
+/// Where the frames of a `SearchRenderer` go.
+#[derive(Debug, Clone)]
+pub enum Target {
+    /// colored text on stdout
+    Terminal,
+    /// colored text collected in memory, see `SearchRenderer::text`
+    Buffer(String),
+    /// numbered PPM images, whose names start with the given prefix
+    Images(PathBuf),
+}
+
+/// Observer, which draws a frame of a search on a `Map` every `every`
+/// expansions and once at the end with the final path over the last frame.
+pub struct SearchRenderer<'a, T, F> {
+    map: &'a Map<T>,
+    glyph: F,
+    every: usize,
+    target: Target,
+    last: Option<Map<Cell>>,
+    frames: usize,
+    /// the first error which occurred while writing images, no more frames are written afterwards
+    pub status: io::Result<()>,
+}
+
+impl<'a, T, F: Fn(&T) -> char> SearchRenderer<'a, T, F> {
+    /// `glyph` determines how the value of a site is shown on the terminal.
+    pub fn terminal(map: &'a Map<T>, glyph: F, every: usize) -> Self {
+        SearchRenderer::new(map, glyph, every, Target::Terminal)
+    }
+
+    /// Like `terminal`, but the frames are kept in memory instead of printed.
+    pub fn buffer(map: &'a Map<T>, glyph: F, every: usize) -> Self {
+        SearchRenderer::new(map, glyph, every, Target::Buffer(String::new()))
+    }
+
+    /// Images are scaled such that every site is a square of 4x4 pixels.
+    pub fn images(map: &'a Map<T>, glyph: F, every: usize, prefix: impl Into<PathBuf>) -> Self {
+        SearchRenderer::new(map, glyph, every, Target::Images(prefix.into()))
+    }
+
+    fn new(map: &'a Map<T>, glyph: F, every: usize, target: Target) -> Self {
+        SearchRenderer {
+            map,
+            glyph,
+            every: every.max(1),
+            target,
+            last: None,
+            frames: 0,
+            status: Ok(()),
+        }
+    }
+
+    /// Number of frames drawn so far.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// All frames drawn so far, if the target is a buffer.
+    pub fn text(&self) -> &str {
+        match &self.target {
+            Target::Buffer(text) => text,
+            _ => "",
+        }
+    }
+
+    /// Roles of all sites, where later roles override earlier ones.
+    pub fn cells<'b>(
+        &self,
+        open: impl Iterator<Item=&'b Coord>,
+        closed: impl Iterator<Item=&'b Coord>,
+        current: Option<Coord>,
+        path: &[Coord]
+    ) -> Map<Cell> {
+        let mut cells = Map {
+            width: self.map.width,
+            height: self.map.height,
+            values: vec![vec![Cell::Unvisited; self.map.width]; self.map.height],
+        };
+        for &c in closed {
+            cells[c] = Cell::Closed;
+        }
+        for &c in open {
+            cells[c] = Cell::Open;
+        }
+        if let Some(c) = current {
+            cells[c] = Cell::Current;
+        }
+        for &c in path {
+            cells[c] = Cell::Path;
+        }
+        cells
+    }
+
+    fn draw(&mut self, title: &str, cells: &Map<Cell>) {
+        if self.status.is_err() {
+            return
+        }
+        self.frames += 1;
+
+        match &self.target {
+            Target::Terminal => println!("{}", self.text_frame(title, cells)),
+            Target::Buffer(_) => {
+                let frame = self.text_frame(title, cells);
+                if let Target::Buffer(text) = &mut self.target {
+                    text.push_str(&frame);
+                    text.push('\n');
+                }
+            }
+            Target::Images(prefix) => {
+                let mut name = prefix.clone().into_os_string();
+                name.push(format!("{:05}.ppm", self.frames));
+                self.status = write_ppm(PathBuf::from(name), cells, 4);
+            }
+        }
+    }
+
+    fn text_frame(&self, title: &str, cells: &Map<Cell>) -> String {
+        let mut frame = format!("{title}\n");
+        for (y, row) in cells.values.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let glyph = (self.glyph)(&self.map[(x, y)]).to_string();
+                let colored = match cell {
+                    Cell::Unvisited => glyph.normal(),
+                    Cell::Open => glyph.yellow(),
+                    Cell::Closed => glyph.blue(),
+                    Cell::Current => glyph.red().bold(),
+                    Cell::Path => glyph.green().bold(),
+                };
+                frame += &colored.to_string();
+            }
+            frame.push('\n');
+        }
+        frame
+    }
+}
+
+/// Writes a binary PPM image with a square of `scale` pixels per site.
+fn write_ppm(path: PathBuf, cells: &Map<Cell>, scale: usize) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    write!(file, "P6\n{} {}\n255\n", cells.width * scale, cells.height * scale)?;
+    for row in &cells.values {
+        for _ in 0..scale {
+            for cell in row {
+                for _ in 0..scale {
+                    file.write_all(&cell.rgb())?;
+                }
+            }
+        }
+    }
+    file.flush()
+}
+
+impl<T, F: Fn(&T) -> char> Observer<Coord> for SearchRenderer<'_, T, F> {
+    fn expanded(&mut self, progress: &Progress<Coord>) {
+        if progress.expansions.is_multiple_of(self.every) {
+            let cells = self.cells(progress.open(), progress.closed(), Some(*progress.current), &[]);
+            self.draw(&format!("after {} expansions", progress.expansions), &cells);
+            self.last = Some(cells);
+        }
+    }
+
+    fn finished(&mut self, path: Option<&[Coord]>) {
+        let mut cells = match self.last.take() {
+            Some(last) => last,
+            None => self.cells(std::iter::empty(), std::iter::empty(), None, &[]),
+        };
+        for cell in cells.values.iter_mut().flatten().filter(|c| **c == Cell::Current) {
+            *cell = Cell::Closed;
+        }
+        for &c in path.unwrap_or(&[]) {
+            cells[c] = Cell::Path;
+        }
+        let title = match path {
+            Some(path) => format!("path of {} steps", path.len() - 1),
+            None => "search finished without a path".to_string(),
+        };
+        self.draw(&title, &cells);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::shortest_path::{Neighborful, astar_observed, bfs_distances_observed};
+
+    use super::*;
+
+    #[test]
+    fn frames() {
+        let map: Map<char> = r"
+            ....
+            .##.
+            ....
+        ".parse().unwrap();
+        let graph = &map;
+        let free = |&c: &Coord| graph.neighbors(c).filter(|&n| map[n] == '.');
+
+        let mut renderer = SearchRenderer::buffer(&map, |&c| c, 2);
+        let (steps, path) = astar_observed(
+            (0, 0),
+            |c| free(c).map(|n| (n, 1)),
+            |&c| <&Map<char>>::distance(c, (3, 2)),
+            |&c| c == (3, 2),
+            &mut renderer
+        ).unwrap();
+        assert_eq!(steps, 5);
+        assert!(renderer.frames() > 1);
+        assert_eq!(renderer.text().matches("after").count(), renderer.frames() - 1);
+        assert!(renderer.text().contains("path of 5 steps"));
+
+        let cells = renderer.cells(std::iter::empty(), std::iter::empty(), None, &path);
+        assert_eq!(cells[(0, 0)], Cell::Path);
+        assert_eq!(cells[(1, 1)], Cell::Unvisited);
+        assert_eq!(cells.values.iter().flatten().filter(|&&c| c == Cell::Path).count(), 6);
+
+        // a search without a goal, one frame per expansion and one at the end
+        let mut renderer = SearchRenderer::buffer(&map, |&c| c, 1);
+        bfs_distances_observed([(0, 0)], free, &mut renderer);
+        assert_eq!(renderer.frames(), 10 + 1);
+        assert!(renderer.text().contains("search finished without a path"));
+
+        let prefix = std::env::temp_dir().join(format!("aoc_search_{}_", std::process::id()));
+        let mut renderer = SearchRenderer::images(&map, |&c| c, 100, &prefix);
+        renderer.finished(Some(&path));
+        assert!(renderer.status.is_ok());
+        let mut name = prefix.into_os_string();
+        name.push("00001.ppm");
+        let image = std::fs::read(&name).unwrap();
+        assert!(image.starts_with(b"P6\n16 12\n255\n"));
+        assert_eq!(image.len(), 13 + 16 * 12 * 3);
+        std::fs::remove_file(name).unwrap();
+    }
+}
@@ -1,26 +1,12 @@
 use scan_fmt::scan_fmt;
 
 use aoc2021::data_str;
-use aoc2021::utils::split_lines;
+use aoc2021::utils::{split_lines, intervals::IntervalSet};
 
-struct Range {
-    start: usize,
-    end: usize,
-}
-
-impl Range {
-    fn new(start: usize, end: usize) -> Self {
-        Range {start, end}
-    }
+type Range = std::ops::Range<usize>;
 
-    fn contains(&self, other: &Range) -> bool {
-        self.start <= other.start && self.end >= other.end
-    }
-
-    fn overlaps(&self, other: &Range) -> bool {
-        self.start <= other.start && self.end >= other.start
-        || self.start <= other.end && self.end >= other.end
-    }
+fn single(range: &Range) -> IntervalSet<usize> {
+    IntervalSet::from_iter([range.clone()])
 }
 
 pub fn run() -> (usize, usize) {
@@ -37,7 +23,7 @@ pub fn run() -> (usize, usize) {
 fn count_full_overlap(ranges: &[(Range, Range)]) -> usize {
     ranges.iter()
         .filter(|(range_elf1, range_elf2)|
-            range_elf1.contains(range_elf2) || range_elf2.contains(range_elf1)
+            single(range_elf1).covers(range_elf2) || single(range_elf2).covers(range_elf1)
         )
         .count()
 }
@@ -45,7 +31,7 @@ fn count_full_overlap(ranges: &[(Range, Range)]) -> usize {
 fn count_partial_overlap(ranges: &[(Range, Range)]) -> usize {
     ranges.iter()
         .filter(|(range_elf1, range_elf2)|
-            range_elf1.overlaps(range_elf2) || range_elf2.contains(range_elf1)
+            single(range_elf1).overlaps(range_elf2)
         )
         .count()
 }
@@ -60,7 +46,8 @@ fn parse(input: &str) -> Vec<(Range, Range)> {
             )
         })
         .map(|(elf1_start, elf1_end, elf2_start, elf2_end)|
-            (Range::new(elf1_start, elf1_end), Range::new(elf2_start, elf2_end))
+            // the input gives inclusive ranges
+            (elf1_start..elf1_end + 1, elf2_start..elf2_end + 1)
         )
         .collect()
 }
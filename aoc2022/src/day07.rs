@@ -56,7 +56,7 @@ impl FromStr for FileHierarchy {
                 pwd.push("/".to_string());
             } else if line.starts_with("$ cd") {
                 let new_dir = line.split(' ')
-                    .last()
+                    .next_back()
                     .ok_or(AdventError::WrongNumberOfElements)?;
                 pwd.push(new_dir.to_string());
                 files.insert(pwd.join("/"), Vec::new());
@@ -64,7 +64,7 @@ impl FromStr for FileHierarchy {
                 // nothing to do
             } else if line.starts_with("dir") {
                 let name = line.split(' ')
-                    .last()
+                    .next_back()
                     .ok_or(AdventError::WrongNumberOfElements)?;
                 pwd.push(name.to_string());
                 let abs_path = pwd.join("/");
@@ -3,9 +3,7 @@ use std::str::FromStr;
 use rustc_hash::FxHashSet;
 use scan_fmt::scan_fmt;
 
-use aoc2021::{data_str, utils::{AdventError, split_lines}};
-
-use crate::utils::coordinate::Point;
+use aoc2021::{data_str, utils::{AdventError, split_lines, Point2 as Point}};
 
 enum Direction {
     Left(usize),
@@ -75,12 +73,12 @@ impl PlanckRope {
         for i in 1..self.knots.len() {
             // if the knot is not adjacent to the previous anymore, it needs to move
             if !self.is_adjacent(i) {
-                let offset = self.knots[i].octant(&self.knots[i-1]);
+                let offset = (self.knots[i-1] - self.knots[i]).signum();
                 self.knots[i] += offset;
 
                 // record the positions of the last one
                 if i == self.knots.len() - 1 {
-                    self.visited_places.insert(self.knots[i].clone());
+                    self.visited_places.insert(self.knots[i]);
                 }
             }
         }
@@ -102,7 +100,7 @@ impl PlanckRope {
     fn is_adjacent(&self, idx: usize) -> bool {
         assert!(idx > 0);
         assert!(idx < self.knots.len());
-        self.knots[idx - 1].distance_l0(&self.knots[idx]) <= 1
+        self.knots[idx - 1].chebyshev(&self.knots[idx]) <= 1
     }
 }
 
@@ -15,7 +15,7 @@ impl FromStr for Instruction {
             Ok(Instruction::Noop)
         } else if s.starts_with("addx") {
             let value = s.split(' ')
-                .last()
+                .next_back()
                 .ok_or(AdventError::WrongNumberOfElements)?
                 .parse()?;
             Ok(Instruction::Addx(value))
@@ -1,11 +1,11 @@
 use std::{str::FromStr, fmt::Debug};
 
-use aoc2021::{data_str, utils::{AdventError, split_lines}};
+use aoc2021::{data_str, utils::{AdventError, split_lines, number_theory::try_lcm_all, checked::{Checked, CheckedIterator}}};
 use itertools::Itertools;
 
 struct Monkey {
     items: Vec<usize>,
-    operation: Box<dyn Fn(usize) -> usize>,
+    operation: Box<dyn Fn(usize) -> Result<usize, AdventError>>,
     target: Box<dyn Fn(usize) -> usize>,
     divisor: usize,
 
@@ -15,7 +15,7 @@ struct Monkey {
 impl Monkey {
     fn new(
         items: Vec<usize>,
-        operation: Box<dyn Fn(usize) -> usize>,
+        operation: Box<dyn Fn(usize) -> Result<usize, AdventError>>,
         target: Box<dyn Fn(usize) -> usize>,
         divisor: usize) -> Monkey
     {
@@ -33,7 +33,7 @@ impl Default for Monkey {
     fn default() -> Self {
         Self {
             items: Default::default(),
-            operation: Box::new(|x| x),
+            operation: Box::new(Ok),
             target: Box::new(|x| x),
             divisor: Default::default(),
             inspections: Default::default()
@@ -61,7 +61,7 @@ impl FromStr for Monkey {
         let items: Vec<usize> = lines.next()
             .ok_or(AdventError::NotEnoughElements)?
             .split(':')
-            .last()
+            .next_back()
             .ok_or(AdventError::NotEnoughElements)?
             .trim()
             .split(',')
@@ -70,7 +70,7 @@ impl FromStr for Monkey {
         let operands = lines.next()
             .ok_or(AdventError::NotEnoughElements)?
             .split('=')
-            .last()
+            .next_back()
             .ok_or(AdventError::NotEnoughElements)?
             .trim()
             .split(' ')
@@ -86,27 +86,27 @@ impl FromStr for Monkey {
                 y => y.parse::<usize>().unwrap()
             };
             match operands[1].as_str() {
-                "+" => op1 + op2,
-                "*" => op1 * op2,
+                "+" => op1.try_add(&op2),
+                "*" => op1.try_mul(&op2),
                 _ => panic!("invalid operator: {operands:?}")
             }
         };
         let divisor: usize = lines.next()
             .ok_or(AdventError::NotEnoughElements)?
             .split(' ')
-            .last()
+            .next_back()
             .ok_or(AdventError::NotEnoughElements)?
             .parse()?;
         let target_true = lines.next()
             .ok_or(AdventError::NotEnoughElements)?
             .split(' ')
-            .last()
+            .next_back()
             .ok_or(AdventError::NotEnoughElements)?
             .parse()?;
         let target_false = lines.next()
             .ok_or(AdventError::NotEnoughElements)?
             .split(' ')
-            .last()
+            .next_back()
             .ok_or(AdventError::NotEnoughElements)?
             .parse()?;
         let target = move |x| if x % divisor == 0 {target_true} else {target_false};
@@ -122,14 +122,14 @@ pub fn run() -> (usize, usize) {
     let mut data2: Vec<Monkey> = parse(input).expect("invalid input");
 
     (
-        monkey_business_level(&mut data1, 20, false),
-        monkey_business_level(&mut data2, 10000, true),
+        monkey_business_level(&mut data1, 20, false).expect("worry level overflow"),
+        monkey_business_level(&mut data2, 10000, true).expect("worry level overflow"),
     )
 }
 
-fn monkey_business_level(monkeys: &mut [Monkey], num_rounds: usize, ridiculous_worry_levels: bool) -> usize {
+fn monkey_business_level(monkeys: &mut [Monkey], num_rounds: usize, ridiculous_worry_levels: bool) -> Result<usize, AdventError> {
     for _i in 0..num_rounds {
-        round(monkeys, ridiculous_worry_levels);
+        round(monkeys, ridiculous_worry_levels)?;
     }
 
     monkeys.iter()
@@ -137,21 +137,19 @@ fn monkey_business_level(monkeys: &mut [Monkey], num_rounds: usize, ridiculous_w
         .sorted()
         .rev()
         .take(2)
-        .product()
+        .checked_product()
 }
 
-fn round(monkeys: &mut [Monkey], ridiculous_worry_levels: bool) {
+fn round(monkeys: &mut [Monkey], ridiculous_worry_levels: bool) -> Result<(), AdventError> {
     // addition and multiplication with modulo is distributive.
-    // in order for all modulos of all monkeys to work, we can multiply them together.
-    let magic_number: usize = monkeys.iter()
-        .map(|m| m.divisor)
-        .product();
+    // in order for all modulos of all monkeys to work, we can use their least common multiple.
+    let magic_number = try_lcm_all(monkeys.iter().map(|m| m.divisor))?;
 
     for i in 0..monkeys.len() {
-        monkeys[i].inspections += monkeys[i].items.len();
+        monkeys[i].inspections = monkeys[i].inspections.try_add(&monkeys[i].items.len())?;
         let monkey = std::mem::take(&mut monkeys[i]);
         for &item in &monkey.items {
-            let mut new = (monkey.operation)(item);
+            let mut new = (monkey.operation)(item)?;
 
             if ridiculous_worry_levels {
                 new %= magic_number;
@@ -165,6 +163,8 @@ fn round(monkeys: &mut [Monkey], ridiculous_worry_levels: bool) {
         monkeys[i] = monkey;
         monkeys[i].items.clear()
     }
+
+    Ok(())
 }
 
 fn parse(input: &str) -> Result<Vec<Monkey>, AdventError> {
@@ -210,16 +210,28 @@ mod tests {
         ";
 
         let mut data: Vec<Monkey> = parse(input).expect("invalid input");
-        assert_eq!(monkey_business_level(&mut data, 20, false), 10605);
+        assert_eq!(monkey_business_level(&mut data, 20, false).unwrap(), 10605);
+
+        let mut data: Vec<Monkey> = parse(input).expect("invalid input");
+        assert_eq!(monkey_business_level(&mut data, 1, true).unwrap(), 24);
 
         let mut data: Vec<Monkey> = parse(input).expect("invalid input");
-        assert_eq!(monkey_business_level(&mut data, 1, true), 24);
+        assert_eq!(monkey_business_level(&mut data, 20, true).unwrap(), 99*103);
 
         let mut data: Vec<Monkey> = parse(input).expect("invalid input");
-        assert_eq!(monkey_business_level(&mut data, 20, true), 99*103);
+        assert_eq!(monkey_business_level(&mut data, 10000, true).unwrap(), 2713310158);
 
+        // a huge common modulus does not keep the worry levels in check
         let mut data: Vec<Monkey> = parse(input).expect("invalid input");
-        assert_eq!(monkey_business_level(&mut data, 10000, true), 2713310158);
+        data.iter_mut().for_each(|m| m.divisor = 1 << 62);
+        assert!(matches!(monkey_business_level(&mut data, 20, true), Err(AdventError::Overflow)));
+
+        // the least common multiple of distinct large primes does not even fit
+        let mut data: Vec<Monkey> = parse(input).expect("invalid input");
+        for (monkey, p) in data.iter_mut().zip([4294967291, 4294967279, 4294967231, 4294967197]) {
+            monkey.divisor = p;
+        }
+        assert!(matches!(monkey_business_level(&mut data, 1, true), Err(AdventError::Overflow)));
 
     }
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
-use std::{str::FromStr, ops::Index};
+use std::str::FromStr;
 
-use aoc2021::{data_str, utils::{AdventError, Map, Coord, shortest_path::{Neighborful, astar}}};
+use aoc2021::{data_str, utils::{AdventError, Map, Coord, shortest_path::{Neighborful, Observer, astar_observed, bfs_distances_observed}}};
 use itertools::Itertools;
 
 struct LetterMap{
@@ -22,15 +22,6 @@ impl Neighborful<Coord> for &LetterMap {
     }
 }
 
-impl Index<Coord> for &LetterMap {
-    type Output = u8;
-
-    fn index(&self, coordinate: Coord) -> &Self::Output {
-        let (x, y) = coordinate;
-        &self.map.values[y][x]
-    }
-}
-
 impl FromStr for LetterMap {
     type Err = AdventError;
 
@@ -85,30 +76,39 @@ pub fn run() -> (usize, usize) {
 
     (
         shortest_path_length(&data),
-        shortest_possible_path_length(&data)
+        shortest_possible_path_length(&data).expect("no path found")
     )
 }
 
-fn shortest_path_length(map: &LetterMap) -> usize {
-    let start = map.start;
+fn climb(map: &LetterMap, start: Coord, observer: &mut impl Observer<Coord>) -> Option<usize> {
     let end = map.end;
-    astar(&map, start, end).len() - 1
+    astar_observed(
+        start,
+        |&c| map.neighbors(c).map(|n| (n, 1)),
+        |&c| <&LetterMap>::distance(c, end),
+        |&c| c == end,
+        observer
+    ).map(|(steps, _path)| steps)
 }
 
-fn shortest_possible_path_length(map: &LetterMap) -> usize {
-    // we should use dynamic programming and remember for each site how far it is to the end,
-    //  but this brute ansatz is fast enough for now (yay, A*)
-    let possible_starts = map.map.values.iter()
-        .enumerate()
-        .flat_map(|(n, values)| values.iter().find_position(|&&c| c == b'a').map(|(pos, _)| (pos, n)));
-    let end = map.end;
-    possible_starts.map(|start| astar(&map, start, end).len() - 1)
-        .min()
-        .unwrap()
+fn shortest_path_length(map: &LetterMap) -> usize {
+    climb(map, map.start, &mut ()).expect("no path found")
+}
+
+fn shortest_possible_path_length(map: &LetterMap) -> Option<usize> {
+    shortest_possible_path_length_observed(map, &mut ())
+}
+
+fn shortest_possible_path_length_observed(map: &LetterMap, observer: &mut impl Observer<Coord>) -> Option<usize> {
+    let possible_starts = map.map.find_all(b'a');
+    // a single search starting from all possible starts at once
+    bfs_distances_observed(possible_starts, |&c| map.neighbors(c), observer).get(&map.end).copied()
 }
 
 #[cfg(test)]
 mod tests {
+    use aoc2021::utils::visualize::SearchRenderer;
+
     use super::*;
 
     #[test]
@@ -123,6 +123,22 @@ mod tests {
 
         let data: LetterMap = input.parse().expect("invalid input");
         assert_eq!(shortest_path_length(&data), 31);
-        assert_eq!(shortest_possible_path_length(&data), 29);
+
+        let mut renderer = SearchRenderer::buffer(&data.map, |&c| c as char, 10);
+        assert_eq!(climb(&data, data.start, &mut renderer), Some(31));
+        assert!(renderer.text().contains("path of 31 steps"));
+        assert_eq!(shortest_possible_path_length(&data), Some(29));
+
+        let mut renderer = SearchRenderer::buffer(&data.map, |&c| c as char, 10);
+        assert_eq!(shortest_possible_path_length_observed(&data, &mut renderer), Some(29));
+        assert!(renderer.frames() > 1);
+
+        // the best start is not the first `a` of its row
+        let data: LetterMap = "SaccccccabcdefghijklmnopqrstuvwxyzE".parse().expect("invalid input");
+        assert_eq!(shortest_possible_path_length(&data), Some(26));
+
+        // no start can climb the `c`
+        let data: LetterMap = "SacE".parse().expect("invalid input");
+        assert_eq!(shortest_possible_path_length(&data), None);
     }
 }
\ No newline at end of file
@@ -32,13 +32,10 @@ impl Ord for Element {
             (Element::List(_lhs), Element::Integer(_rhs)) => self.cmp(&Element::List(vec![other.clone()])),
             (Element::Integer(_lhs), Element::List(_rhs)) => Element::List(vec![self.clone()]).cmp(other),
             (Element::List(lhs), Element::List(rhs)) => {
-                if lhs == rhs {
-                    // this is not correct
-                    return std::cmp::Ordering::Equal
-                }
                 for (l, r) in lhs.iter().zip(rhs.iter()) {
-                    if l != r {
-                        return l.cmp(r)
+                    match l.cmp(r) {
+                        std::cmp::Ordering::Equal => continue,
+                        ordering => return ordering,
                     }
                 }
                 lhs.len().cmp(&rhs.len())
@@ -178,6 +175,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn total_order() {
+        // `[1]` and `1` are ordered equal but not structurally equal, which
+        // must not end the comparison of the lists early
+        let a: Element = "[[1],2]".parse().unwrap();
+        let b: Element = "[1,3]".parse().unwrap();
+        let c: Element = "[[1],3]".parse().unwrap();
+        assert!(a < b);
+        assert_eq!(b.cmp(&c), std::cmp::Ordering::Equal);
+        assert!(a < c);
+    }
+
     #[test]
     fn example() {
 
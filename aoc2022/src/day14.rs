@@ -1,11 +1,9 @@
 use std::{str::FromStr, cmp::{min, max}, fmt::Debug};
 
-use aoc2021::{data_str, utils::{AdventError, split_lines}};
+use aoc2021::{data_str, utils::{AdventError, split_lines, Point2 as Point, automaton::Automaton}};
 use itertools::Itertools;
 use rustc_hash::FxHashSet;
 
-use crate::utils::coordinate::Point;
-
 #[derive(Debug, Clone)]
 struct Rocks {
     map: FxHashSet<Point>,
@@ -15,18 +13,18 @@ struct Rocks {
 
 impl Rocks {
     fn drop_sand(&mut self) -> bool {
-        let mut pos = self.source.clone();
+        let mut pos = self.source;
         let down = Point::new(0, 1);
         let down_left = Point::new(-1, 1);
         let down_right = Point::new(1, 1);
         'outer: loop {
             // we can end as soon as sand is falling into the abyss or blocks the source
-            if pos.y >= self.height || self.map.contains(&self.source) {
+            if pos.y() >= self.height || self.map.contains(&self.source) {
                 return false;
             }
 
             for &candiate in &[&down, &down_left, &down_right] {
-                let new_pos = candiate + &pos;
+                let new_pos = candiate + pos;
                 if !self.map.contains(&new_pos) {
                     pos = new_pos;
                     continue 'outer;
@@ -41,8 +39,8 @@ impl Rocks {
     fn with_floor(mut self) -> Self {
         self.height += 2;
 
-        let left_infty = self.source.x - self.height;
-        let right_infty = self.source.x + self.height;
+        let left_infty = self.source.x() - self.height;
+        let right_infty = self.source.x() + self.height;
 
         for x in left_infty..=right_infty {
             let y = self.height;
@@ -52,12 +50,11 @@ impl Rocks {
         self
     }
 
-    fn count_sand(mut self) -> usize {
-        let mut ctr = 0;
-        while self.drop_sand() {
-            ctr += 1
-        }
-        ctr
+    fn count_sand(self) -> usize {
+        // the last step does not place any sand
+        Automaton::new(self, Rocks::drop_sand)
+            .run_until_fixpoint()
+            .steps() - 1
     }
 }
 
@@ -93,8 +90,8 @@ impl FromStr for Rocks {
             }
         }
 
-        let &height = map.iter()
-            .map(|Point {x: _, y}| y)
+        let height = map.iter()
+            .map(|p| p.y())
             .max()
             .ok_or(AdventError::NotEnoughElements)?;
 
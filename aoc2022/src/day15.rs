@@ -1,12 +1,9 @@
 use std::str::FromStr;
 
-use itertools::Itertools;
 use scan_fmt::scan_fmt;
 use rustc_hash::{FxHashSet, FxHashMap};
 
-use aoc2021::{data_str, utils::{AdventError, split_lines}};
-
-use crate::utils::coordinate::Point;
+use aoc2021::{data_str, utils::{AdventError, split_lines, Point2 as Point, intervals::IntervalSet, geometry::{rotate, unrotate}}};
 
 struct Map {
     sensors: FxHashMap<Point, isize>,
@@ -14,31 +11,72 @@ struct Map {
 }
 
 impl Map {
-    fn extreme_beacons_x(&self) -> (isize, isize) {
-        let (min_x, max_x) = self.beacons.iter()
-            .map(|Point{x, y: _}| *x)
-            .minmax()
-            .into_option()
-            .unwrap();
-        let max_d = self.sensors.values()
-            .max()
-            .unwrap();
-
-        (min_x - max_d, max_x + max_d)
-    }
-
-    fn is_exclusion_zone(&self, coord: Point) -> bool {
+    /// The sites at row `y`, which are closer to a sensor than its beacon.
+    fn covered_at_y(&self, y: isize) -> IntervalSet<isize> {
         self.sensors.iter()
-            .any(|(s, &d)| s.distance_l1(&coord) <= d && !self.beacons.contains(&coord))
+            .map(|(s, &d)| {
+                let reach = d - (s.y() - y).abs();
+                s.x() - reach..s.x() + reach + 1
+            })
+            .collect()
     }
 
     fn excluded_sites_at_y(&self, y: isize) -> usize {
-        // this will be slow, we should rather collect a range for each sensor
-        // and add them
-        let (min_x, max_x) = self.extreme_beacons_x();
+        let beacons: IntervalSet<isize> = self.beacons.iter()
+            .filter(|b| b.y() == y)
+            .map(|b| b.x()..b.x() + 1)
+            .collect();
+
+        self.covered_at_y(y).difference(&beacons).total_length() as usize
+    }
+
+    fn is_covered(&self, site: &Point) -> bool {
+        self.sensors.iter().any(|(s, &d)| s.manhattan(site) <= d)
+    }
+
+    /// The only site within `0..=max`, where no sensor reaches.
+    fn distress_beacon(&self, max: isize) -> Option<Point> {
+        self.enclosed_candidate(max).or_else(|| self.scan_rows(max))
+    }
 
-        (min_x..=max_x).filter(|&x| self.is_exclusion_zone(Point::new(x, y)))
-            .count()
+    /// Fast search among the sites just outside of the sensor ranges.
+    ///
+    /// In rotated coordinates, the ranges are axis-parallel squares. A site
+    /// enclosed by them usually lies just outside of an edge of some square in
+    /// each of the rotated coordinates, so only the crossings of these lines
+    /// are tested. This misses sites bounded otherwise, e.g., by the border of
+    /// the area.
+    fn enclosed_candidate(&self, max: isize) -> Option<Point> {
+        let outside = |axis: usize| -> FxHashSet<isize> {
+            self.sensors.iter()
+                .flat_map(|(s, &d)| {
+                    let c = rotate(s)[axis];
+                    [c - d - 1, c + d + 1]
+                })
+                .collect()
+        };
+        let us = outside(0);
+        let vs = outside(1);
+
+        us.iter()
+            .flat_map(|&u| vs.iter().filter_map(move |&v| unrotate(&Point::new(u, v))))
+            .filter(|p| (0..=max).contains(&p.x()) && (0..=max).contains(&p.y()))
+            .find(|p| !self.is_covered(p))
+    }
+
+    /// Exhaustive search for the first gap in the covered ranges row by row.
+    fn scan_rows(&self, max: isize) -> Option<Point> {
+        (0..=max).find_map(|y|
+            self.covered_at_y(y)
+                .gaps(0..max + 1)
+                .next()
+                .map(|gap| Point::new(gap.start, y))
+        )
+    }
+
+    fn tuning_frequency(&self, max: isize) -> usize {
+        let beacon = self.distress_beacon(max).expect("no distress beacon");
+        (beacon.x() * 4000000 + beacon.y()) as usize
     }
 }
 
@@ -56,7 +94,7 @@ impl FromStr for Map {
             )?;
             let sensor = Point::new(sx, sy);
             let beacon = Point::new(bx, by);
-            let distance = sensor.distance_l1(&beacon);
+            let distance = sensor.manhattan(&beacon);
             sensors.insert(sensor, distance);
             beacons.insert(beacon);
         }
@@ -74,13 +112,15 @@ pub fn run() -> (usize, usize) {
 
     (
         map.excluded_sites_at_y(2000000),
-        0
+        map.tuning_frequency(4000000)
     )
 }
 
 
 #[cfg(test)]
 mod tests {
+    use aoc2021::utils::geometry::manhattan_ball_boundary;
+
     use super::*;
 
     #[test]
@@ -105,5 +145,28 @@ mod tests {
         let map: Map = input.parse().expect("invalid input");
 
         assert_eq!(map.excluded_sites_at_y(10), 26);
+        assert_eq!(map.tuning_frequency(20), 56000011);
+
+        // the distress beacon is adjacent to the range of some sensor
+        let beacon = map.distress_beacon(20).unwrap();
+        assert!(map.sensors.iter().any(|(s, &d)| manhattan_ball_boundary(*s, d + 1).any(|p| p == beacon)));
+        assert_eq!(map.scan_rows(20), Some(beacon));
+    }
+
+    #[test]
+    fn beacon_at_the_border() {
+        // only the corner (0, 0) is not covered, but it is not enclosed by sensor ranges
+        let input = r"
+            Sensor at x=10, y=10: closest beacon is at x=10, y=29
+            Sensor at x=20, y=1: closest beacon is at x=20, y=0
+            Sensor at x=0, y=19: closest beacon is at x=0, y=20
+            Sensor at x=20, y=19: closest beacon is at x=20, y=20
+        ";
+
+        let map: Map = input.parse().expect("invalid input");
+
+        assert_eq!(map.enclosed_candidate(20), None);
+        assert_eq!(map.distress_beacon(20), Some(Point::new(0, 0)));
+        assert_eq!(map.tuning_frequency(20), 0);
     }
 }
\ No newline at end of file
@@ -1,8 +1,3 @@
-#![feature(array_windows)]
-
-
-mod utils;
-
 pub mod day01;
 pub mod day02;
 pub mod day03;
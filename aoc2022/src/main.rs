@@ -64,5 +64,5 @@ mod tests {
     test!{day12: (420, 414)}
     test!{day13: (6623, 23049)}
     test!{day14: (888, 26461)}
-    test!{day15: (5142231, 0)}
+    test!{day15: (5142231, 10884459367718)}
 }
@@ -97,7 +97,7 @@ fn minimum_cube_set(game: &[Cubes]) -> Cubes {
 }
 
 fn power_sum_of_minimum_cube_set(results: &HashMap<u32, Vec<Cubes>>) -> u32 {
-    results.iter().map(|(_id, game)| minimum_cube_set(game))
+    results.values().map(|game| minimum_cube_set(game))
         .map(|cube_set| cube_set.power())
         .sum()
 }
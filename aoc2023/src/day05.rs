@@ -3,37 +3,12 @@ use std::str::FromStr;
 use scan_fmt::scan_fmt;
 
 use aoc2021::data_str;
-use aoc2021::utils::AdventError;
-
-#[derive(Clone)]
-struct Line {
-    dest_start: u64,
-    src_start: u64,
-    length: u64,
-}
-
-#[derive(Clone)]
-struct Map {
-    lines: Vec<Line>,
-}
-
-impl Map {
-    // assume there are no overlaps
-    fn map(&self, src: u64) -> u64 {
-        for line in &self.lines {
-            if (line.src_start..line.src_start+line.length).contains(&src) {
-                let offset = src - line.src_start;
-                return line.dest_start + offset
-            }
-        }
-        src
-    }
-}
+use aoc2021::utils::{AdventError, intervals::{IntervalSet, RangeMap}};
 
 #[derive(Clone)]
 struct Maps {
     seeds: Vec<u64>,
-    maps: Vec<Map>,
+    maps: Vec<RangeMap<u64>>,
 }
 
 impl FromStr for Maps {
@@ -52,22 +27,16 @@ impl FromStr for Maps {
             .collect::<Result<_, _>>()?;
 
         for block in blocks {
-            let mut lines = Vec::new();
+            let mut map = RangeMap::new();
             for line in block.trim().split('\n').skip(1) {
                 let (dest_start, src_start, length) = scan_fmt!(
                     line,
                     "{} {} {}",
                     u64, u64, u64
                 )?;
-                lines.push(
-                    Line {
-                        dest_start,
-                        src_start,
-                        length,
-                    }
-                )
+                map.insert(src_start..src_start+length, dest_start);
             }
-            maps.push(Map { lines });
+            maps.push(map);
         }
         Ok(Maps{
             seeds,
@@ -77,18 +46,19 @@ impl FromStr for Maps {
 }
 
 impl Maps {
-    fn map(&self, mut src: u64) -> u64 {
-        for map in &self.maps {
-            src = map.map(src)
-        }
-        src
+    fn map(&self, src: u64) -> u64 {
+        self.maps.iter().fold(src, |src, map| map.get(src))
     }
 
-    fn expand_seeds_to_ranges(&mut self) {
-        // brute force -- but under two minutes
-        self.seeds = self.seeds.chunks_exact(2)
-            .flat_map(|pair| pair[0]..pair[0]+pair[1])
-            .collect();
+    /// Maps whole ranges of seeds at once instead of every single seed.
+    fn map_ranges(&self, src: IntervalSet<u64>) -> IntervalSet<u64> {
+        self.maps.iter().fold(src, |src, map| map.map_set(&src))
+    }
+
+    fn seed_ranges(&self) -> IntervalSet<u64> {
+        self.seeds.chunks_exact(2)
+            .map(|pair| pair[0]..pair[0]+pair[1])
+            .collect()
     }
 }
 
@@ -96,12 +66,10 @@ pub fn run() -> (u64, u64) {
 
     let input = data_str!("day05");
     let maps: Maps = input.parse().expect("invalid input");
-    let mut maps_extended = maps.clone();
-    maps_extended.expand_seeds_to_ranges();
 
     (
         lowest_location_number(&maps),
-        lowest_location_number(&maps_extended),
+        lowest_location_number_of_ranges(&maps),
     )
 }
 
@@ -109,6 +77,10 @@ fn lowest_location_number(maps: &Maps) -> u64 {
     maps.seeds.iter().map(|&s| maps.map(s)).min().unwrap()
 }
 
+fn lowest_location_number_of_ranges(maps: &Maps) -> u64 {
+    maps.map_ranges(maps.seed_ranges()).min().unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,10 +124,8 @@ mod tests {
         ";
 
         let maps: Maps = input.parse().expect("invalid input");
-        let mut maps_extended = maps.clone();
-        maps_extended.expand_seeds_to_ranges();
 
         assert_eq!(lowest_location_number(&maps), 35);
-        assert_eq!(lowest_location_number(&maps_extended), 46);
+        assert_eq!(lowest_location_number_of_ranges(&maps), 46);
     }
 }
\ No newline at end of file
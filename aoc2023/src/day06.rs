@@ -1,5 +1,5 @@
 use aoc2021::data_str;
-use aoc2021::utils::AdventError;
+use aoc2021::utils::{AdventError, number_theory::quadratic_roots};
 
 pub fn run() -> (u64, u64) {
 
@@ -8,8 +8,8 @@ pub fn run() -> (u64, u64) {
     let records_without_space = parse(&input.replace(' ', "")).expect("invalid input");
 
     (
-        num_winning_times(&records).into_iter().product(),
-        num_winning_times(&records_without_space).into_iter().product(),
+        num_winning_times(&records).expect("overflow").into_iter().product(),
+        num_winning_times(&records_without_space).expect("overflow").into_iter().product(),
     )
 }
 
@@ -39,11 +39,12 @@ fn parse(s: &str) -> Result<Vec<(u64, u64)>, AdventError> {
     )
 }
 
-fn num_winning_times(records: &[(u64, u64)]) -> Vec<u64> {
-    records.iter().map(|(time, distance)| {
-        (0..*time).map(|i| i * (time - i))
-            .filter(|i| i > distance)
-            .count() as u64
+fn num_winning_times(records: &[(u64, u64)]) -> Result<Vec<u64>, AdventError> {
+    records.iter().map(|&(time, distance)| {
+        // holding for `i` wins, if `i (time - i) > distance`, i.e., `i^2 - time i + distance + 1 <= 0`,
+        // whose roots are both in `0..=time`
+        Ok(quadratic_roots(1, -(time as i128), distance as i128 + 1)?
+            .map_or(0, |(low, high)| (high - low + 1) as u64))
     })
     .collect()
 }
@@ -63,7 +64,12 @@ mod tests {
         let records_without_space = parse(&input.replace(' ', ""))
             .expect("invalid input");
 
-        assert_eq!(num_winning_times(&records).into_iter().product::<u64>(), 288);
-        assert_eq!(num_winning_times(&records_without_space).into_iter().product::<u64>(), 71503);
+        assert_eq!(num_winning_times(&records).unwrap().into_iter().product::<u64>(), 288);
+        assert_eq!(num_winning_times(&records_without_space).unwrap().into_iter().product::<u64>(), 71503);
+        // the boundaries are exact, where the distance is reached but not beaten
+        assert_eq!(num_winning_times(&[(10, 25), (10, 24), (4, 100)]).unwrap(), vec![0, 1, 0]);
+        assert_eq!(num_winning_times(&[(4_000_000_000, 3_999_999_999_999_999_999)]).unwrap(), vec![1]);
+        // the square of the time does not fit into an i128
+        assert_eq!(num_winning_times(&[(u64::MAX, 0), (u64::MAX - 1, u64::MAX)]).unwrap(), vec![u64::MAX - 1, u64::MAX - 4]);
     }
 }
\ No newline at end of file
@@ -1,12 +1,9 @@
 use std::collections::HashMap;
 
-use counter::Counter;
 use scan_fmt::scan_fmt;
 
 use aoc2021::data_str;
-use aoc2021::utils::AdventError;
-
-use crate::utils::factorize::factorize;
+use aoc2021::utils::{AdventError, cycles::goal_steps};
 
 
 pub fn run() -> (usize, usize) {
@@ -90,53 +87,22 @@ fn count_steps(map: HashMap<String, Node>, instructions: Vec<Instruction>) -> us
 
 
 fn count_ghost_steps(map: HashMap<String, Node>, instructions: Vec<Instruction>) -> usize {
-    let mut positions: Vec<&str> = map.keys()
-        .filter(|label| label.ends_with('A'))
-        .map(|s| s.as_str())
-        .collect();
-    let mut ctr = 0;
-    let mut loop_lengths = vec![0; positions.len()];
-    loop {
-        let mut finished = true;
-        let direction = &instructions[ctr % instructions.len()];
-        for n in 0..positions.len() {
-            if loop_lengths[n] > 0 {
-                continue;
-            }
-
-            positions[n] = match direction {
-                Instruction::Left => map[positions[n]].left.as_str(),
-                Instruction::Right => map[positions[n]].right.as_str(),
-            };
-
-            if loop_lengths[n] == 0 && positions[n].ends_with('Z') {
-                loop_lengths[n] = ctr + 1;
-            }
-            finished &= loop_lengths[n] > 0;
-        }
-        ctr += 1;
-        if finished {
-            break;
-        }
-    }
-    least_common_multiple(loop_lengths)
-}
-
-fn least_common_multiple(numbers: Vec<usize>) -> usize {
-    let mut prime_factors: Counter<usize> = Counter::new();
-    for num in numbers {
-        let factors = factorize(num);
-        let factors: Counter<usize> = factors.into_iter().collect();
-        for (prime, &multiplicity) in factors.iter() {
-            if prime_factors[prime] < multiplicity {
-                prime_factors[prime] = multiplicity;
-            }
-        }
-    }
+    // the state of a ghost is its node and its position in the instructions,
+    // every ghost eventually runs in a cycle, which might visit multiple end nodes
+    let step = |(position, ctr): &(&str, usize)| {
+        let next = match instructions[*ctr] {
+            Instruction::Left => map[*position].left.as_str(),
+            Instruction::Right => map[*position].right.as_str(),
+        };
+        (next, (ctr + 1) % instructions.len())
+    };
 
-    prime_factors.iter()
-        .map(|(prime, multiplicity)| prime * multiplicity)
-        .product()
+    map.keys()
+        .filter(|label| label.ends_with('A'))
+        .map(|label| goal_steps((label.as_str(), 0), step, |(position, _)| position.ends_with('Z')))
+        .reduce(|a, b| a.merge(&b))
+        .and_then(|hits| hits.iter().find(|&n| n > 0))
+        .expect("the ghosts never arrive at the same time")
 }
 
 #[cfg(test)]
@@ -187,5 +153,25 @@ mod tests {
         let (map, instructions) = parse(input).expect("invalid input");
         assert_eq!(count_ghost_steps(map, instructions), 6);
 
+        // the end node is not reached at the length of the loop,
+        // and the loop of the second ghost visits two end nodes
+        let input = r"
+            L
+
+            11A = (11B, XXX)
+            11B = (11Z, XXX)
+            11Z = (11C, XXX)
+            11C = (11B, XXX)
+            22A = (22Z, XXX)
+            22Z = (22B, XXX)
+            22B = (2BZ, XXX)
+            2BZ = (22C, XXX)
+            22C = (22Z, XXX)
+            XXX = (XXX, XXX)
+        ";
+
+        let (map, instructions) = parse(input).expect("invalid input");
+        assert_eq!(count_ghost_steps(map, instructions), 5);
+
     }
 }
\ No newline at end of file
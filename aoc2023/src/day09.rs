@@ -1,5 +1,5 @@
 use aoc2021::data_str;
-use aoc2021::utils::{split_lines, AdventError};
+use aoc2021::utils::{split_lines, AdventError, polynomial::Newton};
 
 
 pub fn run() -> (i64, i64) {
@@ -34,54 +34,13 @@ fn extrapolated_sum_left(sequences: &[Vec<i64>]) -> i64 {
 }
 
 fn extrapolate(sequence: &[i64]) -> i64 {
-    let differences = differences(&sequence);
-
-    let mut unkowns: Vec<i64> = vec![0];
-    for diffs in differences.iter().rev() {
-        let d = unkowns.last().unwrap();
-        let u = diffs.last().unwrap() + d;
-        unkowns.push(u);
-    }
-
-    *unkowns.last().unwrap()
+    let polynomial = Newton::from_sequence(sequence);
+    polynomial.eval(sequence.len() as i64).try_into().expect("extrapolation out of range")
 }
 
 fn extrapolate_left(sequence: &[i64]) -> i64 {
-    let differences = differences(&sequence);
-
-    let mut unkowns: Vec<i64> = vec![0];
-    for diffs in differences.iter().rev() {
-        let d = unkowns.last().unwrap();
-        let u = diffs.first().unwrap() - d;
-        unkowns.push(u);
-    }
-
-    *unkowns.last().unwrap()
-}
-
-fn differences(sequence: &[i64]) -> Vec<Vec<i64>> {
-    let mut differences: Vec<Vec<i64>> = vec![sequence.iter().cloned().collect()];
-
-    loop {
-        let d = diff(differences.last().unwrap());
-        if d.len() == 0 {
-            panic!()
-        }
-        if d.iter().all(|&i| i == 0) {
-            break;
-        }
-        differences.push(d);
-    }
-
-    differences
-}
-
-fn diff(seq: &[i64]) -> Vec<i64> {
-    seq.iter()
-        .skip(1)
-        .zip(seq)
-        .map(|(i, j)| i - j)
-        .collect()
+    let polynomial = Newton::from_sequence(sequence);
+    polynomial.eval(-1).try_into().expect("extrapolation out of range")
 }
 
 #[cfg(test)]
@@ -1,10 +1,11 @@
-use std::collections::HashSet;
 use std::iter;
 use std::str::FromStr;
 
 use aoc2021::data_str;
-use aoc2021::utils::shortest_path::Neighborful;
-use aoc2021::utils::{AdventError, Map, Coord};
+use aoc2021::utils::regions::flood_fill;
+use aoc2021::utils::geometry::interior_points;
+use aoc2021::utils::shortest_path::{Neighborful, bfs_distances};
+use aoc2021::utils::{AdventError, Map, Coord, Point2};
 
 pub fn run() -> (usize, usize) {
 
@@ -22,13 +23,6 @@ const SOUTH: [char; 4] = ['S', '|', 'F', '7'];
 const EAST: [char; 4] = ['S', '-', 'F', 'L'];
 const WEST: [char; 4] = ['S', '-', '7', 'J'];
 
-#[derive(PartialEq, Clone)]
-enum NodeState {
-    Unseen,
-    Seen,
-    Visited
-}
-
 struct PipeMap {
     map: Map<char>,
 }
@@ -93,147 +87,27 @@ impl PipeMap {
         self.map.find_one('S').expect("Invalid input! Expected 'S'")
     }
 
-    fn dfs(&self) -> Vec<Coord> {
-        let mut visited = vec![vec![NodeState::Unseen; self.map.width]; self.map.height];
-
-        let start = self.find_start();
-        let mut giant_loop = vec![start];
-
-        let mut candidates: Vec<_> = self.neighbors(start).collect();
-        while let Some((cx, cy)) = candidates.pop() {
-            if visited[cy][cx] == NodeState::Visited {
-                continue;
-            }
-            visited[cy][cx] = NodeState::Visited;
-            let unseen_neighbors = self.neighbors((cx, cy))
-                .filter(|&(i, j)| {
-                    let b = visited[j][i] == NodeState::Unseen;
-                    if b {visited[j][i] = NodeState::Seen};
-                    b
-                });
-            candidates.extend(unseen_neighbors);
-            giant_loop.push((cx, cy));
-        }
-
-        giant_loop
-    }
-
-    fn value_of_s(&self) -> char {
-        let s = self.find_start();
-        let mut neighbors = self.neighbors(s);
-        let before = neighbors.next().expect("invalid giant loop");
-        let after = neighbors.next().expect("invalid giant loop");
-
-        if before.0 == after.0 {
-            '-'
-        } else if before.1 == after.1 {
-            '|'
-        } else if s.1 + 1 == before.1 {
-            if s.0 + 1 == after.0 {
-                'F'
-            } else if s.0 - 1 == after.0 {
-                '7'
-            } else {
-                panic!()
-            }
-        } else if s.1 - 1 == before.1 {
-            if s.0 + 1 == after.0 {
-                'L'
-            } else if s.0 - 1 == after.0 {
-                'J'
-            } else {
-                panic!()
-            }
-        } else if s.0 + 1 == before.0 {
-            if s.1 + 1 == after.1 {
-                'F'
-            } else if s.1 - 1 == after.1 {
-                'L'
-            } else {
-                panic!()
-            }
-        } else if s.0 - 1 == before.0 {
-            if s.1 + 1 == after.1 {
-                '7'
-            } else if s.1 - 1 == after.1 {
-                'J'
-            } else {
-                panic!()
-            }
-        } else {
-            panic!()
-        }
+    /// The sites of the loop through 'S', in order along the loop.
+    fn giant_loop(&self) -> Vec<Coord> {
+        flood_fill(&self, self.find_start(), |_| true)
     }
 }
 
 fn distance(map: &PipeMap) -> usize {
-    map.dfs().len() / 2
+    bfs_distances([map.find_start()], |&c| map.neighbors(c))
+        .into_values()
+        .max()
+        .unwrap_or(0)
 }
 
-
-
+/// The tiles of the loop are the boundary of a lattice polygon, such that
+/// Pick's theorem counts the enclosed tiles.
 fn enclosed(map: &PipeMap) -> usize {
-    // a site is inside the loop, if we have an odd number of crossings with the loop via an arbitrary path to the border
-    let mut num_enclosed = 0;
-
-    // TODO: I need to find the correct shape of "S"
-    let giant_loop = map.dfs();
-
-    let giant_loop_set: HashSet<Coord> = giant_loop.iter().cloned().collect();
-    for y in 0..map.map.height {
-        for x in 0..map.map.width {
-            let coord = (x, y);
-            if giant_loop_set.contains(&coord) {
-                continue;
-            }
-            let mut crossings = 0;
-            let mut entry: Option<Coord> = None;
-            let mut last: Option<Coord>;
-            for ix in x..map.map.width {
-                if giant_loop_set.contains(&(ix, y)) {
-                    if entry.is_none() {
-                        entry = Some((ix, y));
-                    }
-                    last = Some((ix, y));
-                } else {
-                    entry = None;
-                    last = None;
-                }
-
-                if entry.is_some() {
-                    let mut entry_value: char = map.map[entry.unwrap()];
-                    let mut last_value: char = map.map[last.unwrap()];
-                    if entry_value == 'S' {
-                        entry_value = map.value_of_s();
-                    }
-                    if last_value == 'S' {
-                        last_value = map.value_of_s();
-                    }
-
-
-                    if last_value == '-' {
-                        continue;
-                    } else if last_value == '|' {
-                        crossings += 1;
-                        entry = None;
-                        continue;
-                    } else if entry != last {
-                        if (NORTH.contains(&entry_value) && NORTH.contains(&last_value))
-                            || (SOUTH.contains(&entry_value) && SOUTH.contains(&last_value)) {
-                            // tangent
-                        } else {
-                            crossings += 1;
-                        }
-                    }
-                }
-            }
-            if crossings % 2 == 1 {
-                num_enclosed += 1;
-            }
-        }
-    }
-
-    num_enclosed
+    let vertices: Vec<Point2> = map.giant_loop()
+        .into_iter()
+        .map(|(x, y)| Point2::new(x as isize, y as isize))
+        .collect();
+    interior_points(&vertices)
 }
 
 
@@ -50,7 +50,7 @@ mod tests {
     test!{day02: (2149, 71274)}
     test!{day03: (527369, 73074886)}
     test!{day04: (24175, 18846301)}
-    test!{#day05: (278755257, 26829166)}
+    test!{day05: (278755257, 26829166)}
     test!{day06: (2344708, 30125202)}
     test!{day07: (246424613, 248256639)}
     test!{day08: (19667, 19185263738117)}
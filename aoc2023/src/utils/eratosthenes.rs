@@ -1,7 +1,34 @@
-use std::cmp;
+/// Default number of integers per segment of the segmented sieve.
+///
+/// As an odd-only bitset a segment needs 16 KiB, such that it fits into the
+/// L1 cache of common CPUs, which is what makes segmenting worthwhile.
+pub const SEGMENT_SIZE: usize = 1 << 18;
+
+/// Fixed size bitset, where a set bit marks an odd composite.
+struct Bits(Vec<u64>);
+
+impl Bits {
+    fn new(n: usize) -> Bits {
+        Bits(vec![0; n.div_ceil(64)])
+    }
+
+    fn set(&mut self, i: usize) {
+        self.0[i / 64] |= 1 << (i % 64);
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.0[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn clear(&mut self) {
+        self.0.fill(0);
+    }
+}
 
 /// Finds all primes smaller or equal than limit and returns them in a vector.
 ///
+/// Only odd numbers are stored, one bit each.
+///
 /// # Examples
 ///
 /// ```
@@ -10,85 +37,199 @@ use std::cmp;
 /// assert_eq!(vec![2, 3, 5, 7, 11, 13, 17, 19], primes);
 /// ```
 pub fn sieve_of_eratosthenes(limit: usize) -> Vec<usize> {
-    let mut sieve: Vec<bool> = vec![true; limit+1];
-    let mut out: Vec<usize> = Vec::new();
-    let ub: usize = (limit as f64).sqrt() as usize;
-
-    // collect seed primes
-    let seed_primes = if ub <= 20 {
-        vec![2, 3, 5, 7, 11, 13, 17, 19]
-    } else {
-        sieve_of_eratosthenes(ub)
-    };
-
-    // start the sieving
-    sieve[0] = false;
-    sieve[1] = false;
-    for i in seed_primes {
-        let mut j = i * 2;
-        while j < limit + 1 {
-            sieve[j] = false;
-            j += i;
-        }
+    if limit < 2 {
+        return Vec::new()
     }
 
-    // read the results
-    for i in 2..=limit {
-        if sieve[i] {
-            out.push(i);
+    // bit i stands for 2i + 1
+    let n = limit.div_ceil(2);
+    let mut composite = Bits::new(n);
+    composite.set(0);
+    let mut p = 3;
+    while p * p <= limit {
+        if !composite.get(p / 2) {
+            for i in (p * p / 2..n).step_by(p) {
+                composite.set(i);
+            }
         }
+        p += 2;
     }
 
-    out
+    std::iter::once(2)
+        .chain((0..n).filter(|&i| !composite.get(i)).map(|i| 2 * i + 1))
+        .collect()
 }
 
+/// Like `sieve_of_eratosthenes`, but sieves `segment_size` (default `SEGMENT_SIZE`)
+/// numbers at a time, such that it needs less memory for large limits.
 pub fn segmented_sieve_of_eratosthenes(limit: usize, segment_size_opt: Option<usize>) -> Vec<usize> {
-    let ub: usize = (limit as f64).sqrt() as usize;
-    let segment_size = segment_size_opt.unwrap_or(28_000);
-
-    let mut sieve: Vec<bool> = vec![true; segment_size];
-    sieve[0] = false;
-    sieve[1] = false;
-    let mut out: Vec<usize> = Vec::new();
-    out.push(2);
-
-    // collect seed primes
-    let seed_primes = if ub <= 20 {
-        vec![3, 5, 7, 11, 13, 17, 19]
-    }
-    else {
-        segmented_sieve_of_eratosthenes(ub, Some(cmp::min(segment_size, limit)))
-    };
-
-    let mut left: Vec<usize> = vec![0; seed_primes.len()];
-    for (n, i) in seed_primes.iter().enumerate() {
-        left[n] = 2*i;
-    }
-
-    for j in 0..limit / segment_size + 1 {
-        let offset = segment_size * j;
-        for (n, i) in seed_primes.iter().enumerate() {
-            let mut x = left[n];
-            while x < segment_size + offset {
-                sieve[x - offset] = false;
-                x += *i;
+    Primes::with_segment_size(segment_size_opt.unwrap_or(SEGMENT_SIZE))
+        .take_while(|&p| p <= limit)
+        .collect()
+}
+
+/// Unbounded iterator over all primes in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use aoc2023::utils::eratosthenes::primes;
+///
+/// assert_eq!(primes().nth(10_000), Some(104_743));
+/// ```
+pub fn primes() -> Primes {
+    Primes::with_segment_size(SEGMENT_SIZE)
+}
+
+/// Number of primes smaller or equal than `n`.
+pub fn prime_pi(n: usize) -> usize {
+    primes().take_while(|&p| p <= n).count()
+}
+
+/// Lazy segmented sieve, see `primes`.
+pub struct Primes {
+    segment_size: usize,
+    /// start of the current segment, bit i stands for `low + 2i + 1`
+    low: usize,
+    composite: Bits,
+    position: usize,
+    /// odd primes up to at least the square root of the end of the current segment
+    seeds: Vec<usize>,
+    two: bool,
+}
+
+impl Primes {
+    pub fn with_segment_size(segment_size: usize) -> Primes {
+        // an even size keeps the segments aligned to odd numbers
+        let segment_size = segment_size.max(2).next_multiple_of(2);
+        let mut primes = Primes {
+            segment_size,
+            low: 0,
+            composite: Bits::new(segment_size / 2),
+            position: 0,
+            seeds: Vec::new(),
+            two: false,
+        };
+        primes.sieve_segment();
+        primes
+    }
+
+    fn sieve_segment(&mut self) {
+        let high = self.low + self.segment_size;
+        if self.seeds.last().is_none_or(|&s| s * s < high) {
+            // grow geometrically, such that the seeds are recomputed rarely
+            let limit = (2 * self.seeds.last().unwrap_or(&0)).max(high.isqrt() + 1);
+            self.seeds = sieve_of_eratosthenes(limit);
+            self.seeds.remove(0);
+        }
+
+        self.composite.clear();
+        for &p in &self.seeds {
+            if p * p >= high {
+                break
+            }
+            // the first odd multiple in the segment, smaller ones are marked by smaller primes
+            let mut start = self.low.div_ceil(p) * p;
+            if start.is_multiple_of(2) {
+                start += p;
             }
-            left[n] = x;
+            for x in (start.max(p * p)..high).step_by(2 * p) {
+                self.composite.set((x - self.low) / 2);
+            }
+        }
+        if self.low == 0 {
+            // 1 is not a prime
+            self.composite.set(0);
         }
-        for n in (1..sieve.len()).step_by(2) {
-            if n + offset > limit {
-                break;
+    }
+}
+
+impl Iterator for Primes {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if !self.two {
+            self.two = true;
+            return Some(2)
+        }
+        loop {
+            while self.position < self.segment_size / 2 {
+                let i = self.position;
+                self.position += 1;
+                if !self.composite.get(i) {
+                    return Some(self.low + 2 * i + 1)
+                }
+            }
+            self.low += self.segment_size;
+            self.position = 0;
+            self.sieve_segment();
+        }
+    }
+}
+
+/// Table of the smallest prime factor of every number up to a limit,
+/// which factorizes these numbers in `O(log n)`.
+///
+/// # Examples
+///
+/// ```
+/// use aoc2023::utils::eratosthenes::SmallestFactor;
+///
+/// let table = SmallestFactor::new(100);
+/// assert_eq!(table.get(91), Some(7));
+/// assert_eq!(table.factorize(84), vec![2, 2, 3, 7]);
+/// ```
+pub struct SmallestFactor {
+    // u32 halves the memory compared to usize
+    factor: Vec<u32>,
+}
+
+impl SmallestFactor {
+    /// Linear sieve, which sets every entry exactly once.
+    pub fn new(limit: usize) -> SmallestFactor {
+        assert!(limit <= u32::MAX as usize, "limit {limit} is too large for the table");
+        let mut factor = vec![0u32; limit + 1];
+        let mut primes: Vec<u32> = Vec::new();
+        for i in 2..=limit {
+            if factor[i] == 0 {
+                factor[i] = i as u32;
+                primes.push(i as u32);
             }
-            if sieve[n] {
-                out.push(n + offset);
+            for &p in &primes {
+                if p > factor[i] || i * p as usize > limit {
+                    break
+                }
+                factor[i * p as usize] = p;
             }
         }
-        for i in (1..segment_size).step_by(2) {
-            sieve[i] = true;
+        SmallestFactor { factor }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.factor.len() - 1
+    }
+
+    /// The smallest prime factor of `n`, `None` for 0 and 1.
+    pub fn get(&self, n: usize) -> Option<usize> {
+        match self.factor[n] {
+            0 => None,
+            p => Some(p as usize),
         }
     }
 
-    out
+    pub fn is_prime(&self, n: usize) -> bool {
+        self.get(n) == Some(n)
+    }
+
+    /// All prime factors of `n` in ascending order.
+    pub fn factorize(&self, mut n: usize) -> Vec<usize> {
+        let mut ret = Vec::new();
+        while let Some(p) = self.get(n) {
+            ret.push(p);
+            n /= p;
+        }
+        ret
+    }
 }
 
 #[cfg(test)]
@@ -98,18 +239,40 @@ mod tests {
     #[test]
     fn sum_of_primes() {
         let vec = sieve_of_eratosthenes(2_000_000);
-        assert!(142913828922 == vec.iter().fold(0, |sum, x| sum + x));
+        assert!(142913828922 == vec.iter().sum::<usize>());
     }
     #[test]
     fn sum_of_primes2() {
         let vec = segmented_sieve_of_eratosthenes(2_000_000, None);
-        println!("{}", vec.iter().fold(0, |sum, x| sum + x));
-        assert!(142913828922 == vec.iter().fold(0, |sum, x| sum + x));
+        assert!(142913828922 == vec.iter().sum::<usize>());
+        // segments smaller than the square root of the limit
+        let vec = segmented_sieve_of_eratosthenes(2_000_000, Some(1000));
+        assert!(142913828922 == vec.iter().sum::<usize>());
     }
 
     #[test]
     fn including_last() {
         let vec = sieve_of_eratosthenes(19);
         assert_eq!(vec![2, 3, 5, 7, 11, 13, 17, 19], vec);
+        assert_eq!(sieve_of_eratosthenes(2), vec![2]);
+        assert!(sieve_of_eratosthenes(1).is_empty());
+        assert_eq!(segmented_sieve_of_eratosthenes(19, Some(3)), vec);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn counting() {
+        assert_eq!(prime_pi(1), 0);
+        assert_eq!(prime_pi(100), 25);
+        assert_eq!(prime_pi(1_000_000), 78_498);
+    }
+
+    #[test]
+    fn smallest_factor() {
+        let table = SmallestFactor::new(10_000);
+        let primes = sieve_of_eratosthenes(10_000);
+        assert_eq!((0..=10_000).filter(|&n| table.is_prime(n)).collect::<Vec<_>>(), primes);
+        for n in 0..=table.limit() {
+            assert_eq!(table.factorize(n), super::super::factorize::factorize(n));
+        }
+    }
+}
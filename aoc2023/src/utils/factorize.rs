@@ -1,3 +1,166 @@
+use std::sync::OnceLock;
+
+use aoc2021::utils::number_theory::{gcd, mod_pow};
+
+/// Primes below this bound are found by trial division with a sieve.
+const SMALL: usize = 1 << 10;
+
+fn small_primes() -> &'static [usize] {
+    static PRIMES: OnceLock<Vec<usize>> = OnceLock::new();
+    PRIMES.get_or_init(|| super::eratosthenes::sieve_of_eratosthenes(SMALL))
+}
+
+fn mul_mod(a: usize, b: usize, m: usize) -> usize {
+    (a as u128 * b as u128 % m as u128) as usize
+}
+
+/// Deterministic Miller–Rabin test, exact for all 64-bit numbers.
+///
+/// # Examples
+///
+/// ```
+/// use aoc2023::utils::factorize::is_prime;
+///
+/// assert!(is_prime(1_000_000_007));
+/// assert!(!is_prime(3_215_031_751));
+/// ```
+pub fn is_prime(n: usize) -> bool {
+    if n < 2 {
+        return false
+    }
+    // these bases are sufficient for n < 3.3e24
+    const BASES: [usize; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    for p in BASES {
+        if n.is_multiple_of(p) {
+            return n == p
+        }
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for a in BASES {
+        let mut x = mod_pow(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness
+            }
+        }
+        return false
+    }
+    true
+}
+
+/// Finds a non-trivial divisor of the odd composite `n` with Brent's variant of Pollard's rho.
+fn pollard_rho(n: usize) -> usize {
+    // some constants of the pseudo random sequence x^2 + c fail, try the next one then
+    for c in 1u128.. {
+        let f = |x: usize| ((mul_mod(x, x, n) as u128 + c) % n as u128) as usize;
+        // differences are multiplied together to save gcd evaluations
+        const BATCH: usize = 128;
+
+        let mut y = 2;
+        let mut x = y;
+        let mut saved = y;
+        let mut q = 1;
+        let mut g = 1;
+        let mut r = 1;
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                saved = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mul_mod(q, x.abs_diff(y), n);
+                }
+                g = gcd(q, n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+
+        // the batch overshot, repeat its steps one by one
+        if g == n {
+            g = 1;
+            while g == 1 {
+                saved = f(saved);
+                g = gcd(x.abs_diff(saved), n);
+            }
+        }
+
+        if g != n {
+            return g
+        }
+    }
+    unreachable!()
+}
+
+fn collect_factors(n: usize, factors: &mut Vec<usize>) {
+    if n == 1 {
+        return
+    }
+    if is_prime(n) {
+        factors.push(n);
+        return
+    }
+    let d = pollard_rho(n);
+    collect_factors(d, factors);
+    collect_factors(n / d, factors);
+}
+
+/// Prime factors of the given number with their multiplicity, ordered by the prime.
+///
+/// Small factors are found by trial division, large ones by Pollard's rho,
+/// such that any 64-bit number is factorized quickly.
+///
+/// # Examples
+///
+/// ```
+/// use aoc2023::utils::factorize::prime_factors;
+///
+/// assert_eq!(prime_factors(360), vec![(2, 3), (3, 2), (5, 1)]);
+/// assert_eq!(prime_factors(600851475143), vec![(71, 1), (839, 1), (1471, 1), (6857, 1)]);
+/// ```
+pub fn prime_factors(mut number: usize) -> Vec<(usize, usize)> {
+    let mut ret = Vec::new();
+    if number == 0 {
+        return ret
+    }
+
+    for &prime in small_primes() {
+        if prime * prime > number {
+            break
+        }
+        let mut multiplicity = 0;
+        while number.is_multiple_of(prime) {
+            number /= prime;
+            multiplicity += 1;
+        }
+        if multiplicity > 0 {
+            ret.push((prime, multiplicity));
+        }
+    }
+
+    let mut large = Vec::new();
+    collect_factors(number, &mut large);
+    large.sort_unstable();
+    for p in large {
+        match ret.last_mut() {
+            Some((q, multiplicity)) if *q == p => *multiplicity += 1,
+            _ => ret.push((p, 1)),
+        }
+    }
+
+    ret
+}
+
 /// Finds all primefactors of the given number, returned as a vector.
 ///
 /// # Examples
@@ -7,26 +170,37 @@
 ///
 /// assert_eq!(vec![7, 191], factorize(1337));
 /// ```
-pub fn factorize(mut number: usize) -> Vec<usize> {
-    let primes = super::eratosthenes::sieve_of_eratosthenes((number as f64).sqrt() as usize + 1);
-    let mut ret = Vec::new();
+pub fn factorize(number: usize) -> Vec<usize> {
+    prime_factors(number).into_iter()
+        .flat_map(|(p, multiplicity)| std::iter::repeat_n(p, multiplicity))
+        .collect()
+}
 
-    'outer: while number > 1 {
-        for prime in &primes {
-            if number % prime == 0 {
-                number /= prime;
-                ret.push(*prime);
-                continue 'outer;
+/// All divisors of the given number in ascending order.
+pub fn divisors(number: usize) -> Vec<usize> {
+    if number == 0 {
+        return Vec::new()
+    }
+    let mut ret = vec![1];
+    for (p, multiplicity) in prime_factors(number) {
+        let n = ret.len();
+        let mut power = 1;
+        for _ in 0..multiplicity {
+            power *= p;
+            for i in 0..n {
+                ret.push(ret[i] * power);
             }
         }
-        // the remainder will be prime
-        ret.push(number);
-        break;
     }
-
+    ret.sort_unstable();
     ret
 }
 
+/// Euler's totient, the number of integers in `1..=n` coprime to `n`.
+pub fn totient(number: usize) -> usize {
+    prime_factors(number).into_iter()
+        .fold(number, |phi, (p, _)| phi / p * (p - 1))
+}
 
 #[cfg(test)]
 mod tests {
@@ -37,5 +211,40 @@ mod tests {
         assert!(factorize(12) == vec![2, 2, 3]);
         assert!(factorize(13) == vec![13]);
         assert!(factorize(14) == vec![2, 7]);
+        assert!(factorize(1).is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn large() {
+        // a product of two primes above the trial division bound
+        assert_eq!(prime_factors(1_000_000_007 * 998_244_353), vec![(998_244_353, 1), (1_000_000_007, 1)]);
+        assert_eq!(prime_factors(4_294_967_291 * 4_294_967_291), vec![(4_294_967_291, 2)]);
+        assert_eq!(prime_factors(u64::MAX as usize), vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (6700417, 1)]);
+        assert!(is_prime(18_446_744_073_709_551_557));
+
+        // strong pseudoprimes to several bases and Carmichael numbers
+        for n in [561, 2047, 1_373_653, 25_326_001, 3_215_031_751, 341_550_071_728_321] {
+            assert!(!is_prime(n));
+            assert_eq!(factorize(n).into_iter().product::<usize>(), n);
+        }
+    }
+
+    #[test]
+    fn sieve_agrees() {
+        let primes = super::super::eratosthenes::sieve_of_eratosthenes(10_000);
+        assert_eq!((0..=10_000).filter(|&n| is_prime(n)).collect::<Vec<_>>(), primes);
+    }
+
+    #[test]
+    fn divisors_and_totient() {
+        assert_eq!(divisors(28), vec![1, 2, 4, 7, 14, 28]);
+        assert_eq!(divisors(1), vec![1]);
+        assert_eq!(totient(1), 1);
+        assert_eq!(totient(36), 12);
+        assert_eq!(totient(97), 96);
+        for n in 1..200 {
+            let coprime = (1..=n).filter(|&k| gcd(k, n) == 1).count();
+            assert_eq!(totient(n), coprime);
+        }
+    }
+}